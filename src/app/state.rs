@@ -3,12 +3,22 @@
 //! This module contains the core application state structure and enums
 //! that define the different states and modes the application can be in.
 
-use ratatui::widgets::ListState;
+use ratatui::{layout::Rect, text::Line, widgets::ListState};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-use crate::ifdb::{IfdbClient, Game, GameDetails};
+use crate::cache::ResponseCache;
+use crate::config::{self, GameFormat};
+use crate::cover_art::{self, CoverImage, GraphicsProtocol};
+use crate::ifdb::{IfdbClient, Game, GameDetails, PlayTime, SearchFilters};
+use crate::inspect::StoryInfo;
 use crate::storage::{GameStorage, LocalGame, SaveFile};
-use crate::launcher::Launcher;
+use crate::hooks::Hooks;
+use crate::launcher::{LaunchOptions, Launcher};
 use crate::network::NetworkChecker;
+use crate::settings::Settings;
+use crate::text_input::TextInput;
+use crate::transcript::Transcript;
 
 /// Main TUI application state
 pub struct TuiApp {
@@ -29,9 +39,25 @@ pub struct TuiApp {
     /// Current tab index
     pub(crate) current_tab: usize,
     /// Search input state
-    pub(crate) search_input: String,
-    /// Search results
+    pub(crate) search_input: TextInput,
+    /// Every result of the current search, as IFDB returned them
+    pub(crate) all_search_results: Vec<Game>,
+    /// Search results shown in the browse list
     pub(crate) search_results: Vec<Game>,
+    /// Badges for each shown search result
+    pub(crate) search_badges: Vec<ResultBadges>,
+    /// Whether results glkcli cannot play are listed too
+    pub(crate) show_all_results: bool,
+    /// Formats whose interpreter was found when the TUI started
+    pub(crate) installed_formats: Vec<GameFormat>,
+    /// Whether each listed game's details show it is commercial, by TUID
+    pub(crate) commercial_games: HashMap<String, bool>,
+    /// Filters applied to IFDB searches
+    pub(crate) search_filters: SearchFilters,
+    /// Search filters being edited
+    pub(crate) search_filters_form: Option<SearchFiltersForm>,
+    /// Whether the search results came from an expired cache entry
+    pub(crate) search_results_stale: bool,
     /// Selected game in search results
     pub(crate) search_selection: ListState,
     /// Current page for search results
@@ -48,8 +74,38 @@ pub struct TuiApp {
     pub(crate) save_files: Vec<SaveFile>,
     /// Selected save file
     pub(crate) save_selection: ListState,
+    /// Recorded transcripts for the selected game
+    pub(crate) transcripts: Vec<Transcript>,
+    /// Selected transcript
+    pub(crate) transcript_selection: ListState,
+    /// Plain text of the transcript being viewed
+    pub(crate) transcript_text: String,
+    /// Scroll offset in the transcript view
+    pub(crate) transcript_scroll: u16,
+    /// Game whose format could not be detected, waiting for the player to pick one
+    pub(crate) format_picker_game: Option<LocalGame>,
+    /// Formats offered in the picker, and whether their interpreter is installed
+    pub(crate) format_options: Vec<(GameFormat, bool)>,
+    /// Selected format in the picker
+    pub(crate) format_selection: ListState,
     /// Current game details being viewed
     pub(crate) current_game_details: Option<GameDetails>,
+    /// Whether the current game details came from an expired cache entry
+    pub(crate) current_details_stale: bool,
+    /// Header metadata of the downloaded game whose details are shown, by TUID
+    pub(crate) current_story_info: Option<(String, StoryInfo)>,
+    /// How cover art is drawn in this terminal
+    pub(crate) graphics: GraphicsProtocol,
+    /// Cover art for the game details being viewed
+    pub(crate) current_cover: Option<CoverImage>,
+    /// Whether the cover fills the whole details view
+    pub(crate) cover_enlarged: bool,
+    /// Where the renderer wants an image-protocol cover drawn this frame
+    pub(crate) pending_cover_area: Option<Rect>,
+    /// Image-protocol cover currently on screen
+    pub(crate) drawn_cover: Option<(Rect, PathBuf)>,
+    /// Text-rendered cover and the (width, height) it was rendered at
+    pub(crate) cover_lines: Option<((u16, u16), Vec<Line<'static>>)>,
     /// Loading state
     pub(crate) loading: bool,
     /// Status message
@@ -62,8 +118,245 @@ pub struct TuiApp {
     pub(crate) import_file_path: String,
     /// TUID of game being imported (for commercial games)
     pub(crate) import_game_tuid: Option<String>,
+    /// Launch options being edited for a downloaded game
+    pub(crate) launch_options_form: Option<LaunchOptionsForm>,
     /// Flag to indicate terminal needs full redraw
     pub(crate) needs_redraw: bool,
+    /// Destructive action waiting for the user to confirm it
+    pub(crate) pending_action: Option<PendingAction>,
+    /// User settings loaded at startup
+    pub(crate) settings: Settings,
+    /// Set when glkcli was asked to terminate while a game was running
+    pub(crate) quit_requested: bool,
+}
+
+/// An action that only runs once the user confirms it
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendingAction {
+    /// Move a downloaded game to the trash
+    DeleteGame { tuid: String, title: String },
+}
+
+impl PendingAction {
+    /// Question shown in the status bar while waiting for confirmation
+    pub fn prompt(&self) -> String {
+        match self {
+            PendingAction::DeleteGame { title, .. } => {
+                format!("Delete '{}'? It can be restored with 'u'. (y/n)", title)
+            }
+        }
+    }
+}
+
+/// Launch options of a downloaded game, as edited in its details view
+///
+/// Text fields are kept as typed and only parsed when the form is saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchOptionsForm {
+    pub tuid: String,
+    /// Format to run as, or `None` to detect it
+    pub format: Option<GameFormat>,
+    pub interpreter: String,
+    /// Arguments separated by whitespace
+    pub args: String,
+    /// `NAME=value` pairs separated by whitespace
+    pub env: String,
+    /// Hooks are only edited in `metadata.json`, but saving must keep them
+    pub hooks: Hooks,
+    /// Index into [`LaunchOptionsForm::FIELDS`] of the field being edited
+    pub field: usize,
+}
+
+impl LaunchOptionsForm {
+    pub const FIELDS: [&'static str; 4] = ["Format", "Interpreter", "Arguments", "Environment"];
+
+    pub fn new(game: &LocalGame) -> Self {
+        let options = &game.launch_options;
+        LaunchOptionsForm {
+            tuid: game.tuid.clone(),
+            format: options.format,
+            interpreter: options.interpreter.clone().unwrap_or_default(),
+            args: options.args_string(),
+            env: options.env_string(),
+            hooks: options.hooks.clone(),
+            field: 0,
+        }
+    }
+
+    /// Step through detection and then each playable format
+    pub fn cycle_format(&mut self, forward: bool) {
+        let choices: Vec<Option<GameFormat>> = std::iter::once(None)
+            .chain(GameFormat::PLAYABLE.into_iter().map(Some))
+            .collect();
+        let current = choices.iter().position(|f| *f == self.format).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+        self.format = choices[next];
+    }
+
+    /// Text of the selected field, unless it is the format
+    pub fn text_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            1 => Some(&mut self.interpreter),
+            2 => Some(&mut self.args),
+            3 => Some(&mut self.env),
+            _ => None,
+        }
+    }
+
+    /// The options the form describes
+    pub fn to_options(&self) -> anyhow::Result<LaunchOptions> {
+        let interpreter = self.interpreter.trim();
+        Ok(LaunchOptions {
+            format: self.format,
+            interpreter: (!interpreter.is_empty()).then(|| interpreter.to_string()),
+            args: LaunchOptions::parse_args(&self.args)?,
+            env: LaunchOptions::parse_env(&self.env)?,
+            hooks: self.hooks.clone(),
+        })
+    }
+}
+
+/// Filters for IFDB searches, as edited above the browse list
+///
+/// Like [`LaunchOptionsForm`], text is kept as typed and years are only
+/// parsed when the form is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchFiltersForm {
+    pub genre: String,
+    pub system: String,
+    pub min_rating: Option<u8>,
+    pub year_from: String,
+    pub year_to: String,
+    pub language: String,
+    pub play_time: Option<PlayTime>,
+    /// Index into [`SearchFiltersForm::FIELDS`] of the field being edited
+    pub field: usize,
+}
+
+impl SearchFiltersForm {
+    pub const FIELDS: [&'static str; 7] =
+        ["Genre", "System", "Min rating", "Year from", "Year to", "Language", "Play time"];
+
+    pub fn new(filters: &SearchFilters) -> Self {
+        let year = |y: Option<u16>| y.map(|y| y.to_string()).unwrap_or_default();
+        SearchFiltersForm {
+            genre: filters.genre.clone().unwrap_or_default(),
+            system: filters.system.clone().unwrap_or_default(),
+            min_rating: filters.min_rating,
+            year_from: year(filters.year_from),
+            year_to: year(filters.year_to),
+            language: filters.language.clone().unwrap_or_default(),
+            play_time: filters.play_time,
+            field: 0,
+        }
+    }
+
+    /// Step the rating or play time of the selected field through its choices
+    pub fn cycle(&mut self, forward: bool) {
+        fn step<T: Copy + PartialEq>(choices: &[Option<T>], current: Option<T>, forward: bool) -> Option<T> {
+            let i = choices.iter().position(|c| *c == current).unwrap_or(0);
+            let next = if forward { (i + 1) % choices.len() } else { (i + choices.len() - 1) % choices.len() };
+            choices[next]
+        }
+
+        match self.field {
+            2 => {
+                let ratings: Vec<Option<u8>> = std::iter::once(None).chain((1..=5).map(Some)).collect();
+                self.min_rating = step(&ratings, self.min_rating, forward);
+            }
+            6 => {
+                let bands: Vec<Option<PlayTime>> =
+                    std::iter::once(None).chain(PlayTime::ALL.into_iter().map(Some)).collect();
+                self.play_time = step(&bands, self.play_time, forward);
+            }
+            _ => {}
+        }
+    }
+
+    /// Text of the selected field, unless it is chosen from a list
+    pub fn text_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            0 => Some(&mut self.genre),
+            1 => Some(&mut self.system),
+            3 => Some(&mut self.year_from),
+            4 => Some(&mut self.year_to),
+            5 => Some(&mut self.language),
+            _ => None,
+        }
+    }
+
+    /// The filters the form describes
+    pub fn to_filters(&self) -> anyhow::Result<SearchFilters> {
+        let text = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let year = |name: &str, value: &str| -> anyhow::Result<Option<u16>> {
+            text(value)
+                .map(|y| y.parse().map_err(|_| anyhow::anyhow!("{} must be a year, not '{}'", name, y)))
+                .transpose()
+        };
+
+        Ok(SearchFilters {
+            genre: text(&self.genre),
+            system: text(&self.system),
+            language: text(&self.language),
+            min_rating: self.min_rating,
+            year_from: year("Year from", &self.year_from)?,
+            year_to: year("Year to", &self.year_to)?,
+            play_time: self.play_time,
+            ..SearchFilters::default()
+        })
+    }
+}
+
+/// Whether a search result can be played with the interpreters installed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Playability {
+    /// Its format's interpreter was found
+    Playable(GameFormat),
+    /// Played in the web browser
+    Browser,
+    /// Its format is known but the interpreter is not installed
+    NeedsInterpreter(GameFormat),
+    /// IFDB names no development system, so it may well be playable
+    Unlisted,
+    /// Written with a system glkcli cannot run
+    Unsupported,
+}
+
+impl Playability {
+    /// Classify a game by its IFDB development system, given which formats
+    /// have an interpreter installed
+    pub fn of(devsys: Option<&str>, installed: &[GameFormat]) -> Self {
+        let Some(devsys) = devsys else {
+            return Playability::Unlisted;
+        };
+        match config::format_for_devsys(devsys) {
+            Some(format) if !installed.contains(&format) => Playability::NeedsInterpreter(format),
+            Some(GameFormat::Web) => Playability::Browser,
+            Some(format) => Playability::Playable(format),
+            None => Playability::Unsupported,
+        }
+    }
+
+    /// Whether the result is listed when not showing everything
+    pub fn is_shown_by_default(&self) -> bool {
+        !matches!(self, Playability::NeedsInterpreter(_) | Playability::Unsupported)
+    }
+}
+
+/// What the browse list shows next to a search result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultBadges {
+    pub playability: Playability,
+    /// Known from cached details only, since listings do not say
+    pub commercial: bool,
+    pub downloaded: bool,
 }
 
 /// Application state - which view/screen is currently active
@@ -76,6 +369,12 @@ pub enum AppState {
     GameDetails,
     /// Showing save files dialog
     SaveFilesDialog,
+    /// Listing recorded transcripts for a game
+    TranscriptsDialog,
+    /// Reading a transcript as plain text
+    TranscriptView,
+    /// Choosing the format of a game detection could not identify
+    FormatPicker,
     /// Downloading a game (transition state)
     Download,
     /// Viewing downloaded games
@@ -96,21 +395,42 @@ pub enum InputMode {
     Confirmation,
     /// Entering file path for game import
     ImportingFile,
+    /// Editing a downloaded game's launch options
+    EditingLaunchOptions,
+    /// Editing the IFDB search filters
+    EditingSearchFilters,
 }
 
 impl TuiApp {
     /// Create a new TuiApp instance
     pub async fn new(debug: bool, assume_online: bool) -> anyhow::Result<Self> {
-        let ifdb_client = IfdbClient::new()?;
-        let storage = GameStorage::new()?;
-        let launcher = Launcher::new()?;
+        let settings = Settings::load().unwrap_or_else(|e| {
+            log::warn!("Using default settings: {}", e);
+            Settings::default()
+        });
+        let mut ifdb_client = IfdbClient::from_settings(&settings)?;
+        match ResponseCache::new() {
+            Ok(cache) => ifdb_client = ifdb_client.with_cache(cache),
+            Err(e) => log::warn!("IFDB response cache unavailable: {}", e),
+        }
+        let graphics = cover_art::select_protocol(settings.cover_art.as_deref());
+        let storage = GameStorage::new()?.with_ifdb_url(ifdb_client.base_url());
+        let launcher = Launcher::from_settings(&settings)?;
+        // Interpreters are only looked up again when settings change, which
+        // means restarting
+        let installed_formats = GameFormat::PLAYABLE
+            .into_iter()
+            .filter(|format| launcher.interpreter_path(*format).is_some())
+            .collect();
         let network = NetworkChecker::new(debug, assume_online);
 
         // Check network connectivity
         let is_online = network.is_connected().await;
+        ifdb_client.set_offline(!is_online);
         
         if debug {
             log::info!("Network connectivity: {}", if is_online { "online" } else { "offline" });
+            log::info!("Cover art protocol: {:?}", graphics);
         }
 
         Ok(TuiApp {
@@ -122,8 +442,16 @@ impl TuiApp {
             is_online,
             debug,
             current_tab: 0,
-            search_input: String::new(),
+            search_input: TextInput::default(),
+            all_search_results: Vec::new(),
             search_results: Vec::new(),
+            search_badges: Vec::new(),
+            show_all_results: false,
+            installed_formats,
+            commercial_games: HashMap::new(),
+            search_filters: SearchFilters::default(),
+            search_filters_form: None,
+            search_results_stale: false,
             search_selection: ListState::default(),
             current_search_page: 1,
             has_more_search_results: true,
@@ -132,14 +460,33 @@ impl TuiApp {
             downloaded_selection: ListState::default(),
             save_files: Vec::new(),
             save_selection: ListState::default(),
+            transcripts: Vec::new(),
+            transcript_selection: ListState::default(),
+            transcript_text: String::new(),
+            transcript_scroll: 0,
+            format_picker_game: None,
+            format_options: Vec::new(),
+            format_selection: ListState::default(),
             current_game_details: None,
+            current_details_stale: false,
+            current_story_info: None,
+            graphics,
+            current_cover: None,
+            cover_enlarged: false,
+            pending_cover_area: None,
+            drawn_cover: None,
+            cover_lines: None,
             loading: false,
             status_message: None,
             status_message_time: None,
             input_mode: InputMode::Normal,
             import_file_path: String::new(),
             import_game_tuid: None,
+            launch_options_form: None,
             needs_redraw: false,
+            pending_action: None,
+            settings,
+            quit_requested: false,
         })
     }
 
@@ -149,6 +496,13 @@ impl TuiApp {
         self.status_message_time = Some(std::time::Instant::now());
     }
 
+    /// Ask the user to confirm an action before running it
+    pub(crate) fn request_confirmation(&mut self, action: PendingAction) {
+        self.clear_status_message();
+        self.pending_action = Some(action);
+        self.input_mode = InputMode::Confirmation;
+    }
+
     /// Clear the status message
     pub(crate) fn clear_status_message(&mut self) {
         self.status_message = None;
@@ -164,3 +518,54 @@ impl TuiApp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_filters_form() {
+        let mut form = SearchFiltersForm::new(&SearchFilters::default());
+        form.genre.push_str(" Fantasy ");
+        form.year_from.push_str("2001");
+        form.field = 2;
+        form.cycle(false);
+        assert_eq!(form.min_rating, Some(5));
+        form.field = 6;
+        form.cycle(true);
+        assert_eq!(form.play_time, Some(PlayTime::Under15Minutes));
+
+        let filters = form.to_filters().unwrap();
+        assert_eq!(filters.genre.as_deref(), Some("Fantasy"));
+        assert_eq!(filters.year_from, Some(2001));
+        assert_eq!(filters.year_to, None);
+        assert_eq!(SearchFiltersForm::new(&filters).to_filters().unwrap(), filters);
+
+        form.year_to.push_str("soon");
+        assert!(form.to_filters().is_err());
+    }
+
+    #[test]
+    fn test_playability() {
+        let installed = [GameFormat::ZCode, GameFormat::Web];
+
+        assert_eq!(Playability::of(Some("Inform 6"), &installed), Playability::Playable(GameFormat::ZCode));
+        assert_eq!(Playability::of(Some("Twine"), &installed), Playability::Browser);
+        assert_eq!(
+            Playability::of(Some("TADS 3"), &installed),
+            Playability::NeedsInterpreter(GameFormat::Tads)
+        );
+        assert_eq!(Playability::of(None, &installed), Playability::Unlisted);
+        assert_eq!(Playability::of(Some("Custom"), &installed), Playability::Unsupported);
+
+        // Without a browser, web games need one like any other interpreter
+        assert_eq!(
+            Playability::of(Some("Twine"), &[GameFormat::ZCode]),
+            Playability::NeedsInterpreter(GameFormat::Web)
+        );
+
+        assert!(Playability::Unlisted.is_shown_by_default());
+        assert!(!Playability::Unsupported.is_shown_by_default());
+        assert!(!Playability::NeedsInterpreter(GameFormat::Tads).is_shown_by_default());
+    }
+}
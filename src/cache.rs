@@ -0,0 +1,276 @@
+//! On-disk cache of IFDB responses
+//!
+//! Responses are stored as JSON files under `~/.glkcli/cache`, together with
+//! the `ETag` / `Last-Modified` validators needed to revalidate them. Cached
+//! data lets the TUI keep working when the network is down or IFDB fails.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How long game details are considered fresh
+pub const DETAILS_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How long search and browse listings are considered fresh
+pub const LISTING_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How long unused entries are kept before `prune` deletes them
+pub const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Manages cached IFDB responses on disk
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+/// A single cached response body and its validators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    pub fetched_at: SystemTime,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl CacheEntry {
+    /// Check whether the entry is younger than `ttl`
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        match SystemTime::now().duration_since(self.fetched_at) {
+            Ok(age) => age < ttl,
+            // Timestamp in the future (clock changed) - treat as fresh
+            Err(_) => true,
+        }
+    }
+}
+
+/// Identifies a cached response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheKey {
+    /// Game details, keyed by TUID
+    Details(String),
+    /// Search or browse listing, keyed by request URL
+    Listing(String),
+}
+
+impl CacheKey {
+    fn relative_path(&self) -> PathBuf {
+        match self {
+            CacheKey::Details(tuid) => PathBuf::from("details").join(format!("{}.json", sanitize_key(tuid))),
+            CacheKey::Listing(url) => PathBuf::from("listings").join(format!("{:016x}.json", fnv1a(url.as_bytes()))),
+        }
+    }
+}
+
+impl ResponseCache {
+    /// Open the cache in `~/.glkcli/cache`
+    pub fn new() -> Result<Self> {
+        let dir = dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine home directory"))?
+            .join(".glkcli")
+            .join("cache");
+
+        Self::with_dir(dir)
+    }
+
+    /// Open a cache rooted at an arbitrary directory
+    pub fn with_dir(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir).context("Failed to create cache directory")?;
+        Ok(ResponseCache { dir })
+    }
+
+    /// Look up a cached entry
+    ///
+    /// Missing or unreadable entries are reported as `None` - a broken cache
+    /// file should never stop the caller from going to the network.
+    pub fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.dir.join(key.relative_path())).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Ignoring corrupt cache entry {:?}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Store an entry, replacing any previous one
+    pub fn put(&self, key: &CacheKey, entry: &CacheEntry) -> Result<()> {
+        let path = self.dir.join(key.relative_path());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create cache directory")?;
+        }
+
+        let content = serde_json::to_string(entry)
+            .context("Failed to serialize cache entry")?;
+
+        // Write to a temporary file first so a crash never leaves a half-written entry
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content).context("Failed to write cache entry")?;
+        fs::rename(&tmp_path, &path).context("Failed to replace cache entry")?;
+
+        Ok(())
+    }
+
+    /// Path where the cover image for a game is cached
+    pub fn cover_path(&self, tuid: &str) -> PathBuf {
+        self.dir.join("covers").join(sanitize_key(tuid))
+    }
+
+    /// Store a cover image for a game
+    pub fn put_cover(&self, tuid: &str, data: &[u8]) -> Result<PathBuf> {
+        let path = self.cover_path(tuid);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create cache directory")?;
+        }
+
+        fs::write(&path, data).context("Failed to write cached cover art")?;
+        Ok(path)
+    }
+
+    /// Delete cached responses and covers last written more than `max_age` ago
+    ///
+    /// Every search, page and filter combination gets its own listing, so
+    /// without pruning the cache only ever grows. Returns the number of files
+    /// removed.
+    pub fn prune(&self, max_age: Duration) -> Result<usize> {
+        let mut removed = 0;
+
+        for sub in ["details", "listings", "covers"] {
+            let entries = match fs::read_dir(self.dir.join(sub)) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e).context("Failed to read cache directory"),
+            };
+
+            for entry in entries.flatten() {
+                let expired = entry
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                    .map(|age| age >= max_age)
+                    .unwrap_or(false);
+
+                if expired && entry.path().is_file() {
+                    fs::remove_file(entry.path())
+                        .with_context(|| format!("Failed to remove {}", entry.path().display()))?;
+                    removed += 1;
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Make a key safe to use as a file name
+fn sanitize_key(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// 64-bit FNV-1a hash, used to derive stable file names from URLs
+fn fnv1a(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(body: &str, fetched_at: SystemTime) -> CacheEntry {
+        CacheEntry {
+            url: "https://ifdb.org/viewgame?json&id=abc".to_string(),
+            fetched_at,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_put_and_get_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let cache = ResponseCache::with_dir(temp.path().to_path_buf()).unwrap();
+        let key = CacheKey::Details("0dbnusxunq7fw5ro".to_string());
+
+        assert!(cache.get(&key).is_none());
+
+        cache.put(&key, &entry("{\"a\":1}", SystemTime::now())).unwrap();
+        let cached = cache.get(&key).expect("entry should be cached");
+        assert_eq!(cached.body, "{\"a\":1}");
+        assert_eq!(cached.etag.as_deref(), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_listing_keys_are_distinct() {
+        let temp = TempDir::new().unwrap();
+        let cache = ResponseCache::with_dir(temp.path().to_path_buf()).unwrap();
+        let a = CacheKey::Listing("https://ifdb.org/search?json&searchfor=zork".to_string());
+        let b = CacheKey::Listing("https://ifdb.org/search?json&searchfor=anchorhead".to_string());
+
+        cache.put(&a, &entry("zork", SystemTime::now())).unwrap();
+        cache.put(&b, &entry("anchorhead", SystemTime::now())).unwrap();
+
+        assert_eq!(cache.get(&a).unwrap().body, "zork");
+        assert_eq!(cache.get(&b).unwrap().body, "anchorhead");
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_ignored() {
+        let temp = TempDir::new().unwrap();
+        let cache = ResponseCache::with_dir(temp.path().to_path_buf()).unwrap();
+        let key = CacheKey::Details("broken".to_string());
+
+        let path = temp.path().join(key.relative_path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_entry_freshness() {
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(3600);
+
+        assert!(entry("", now).is_fresh(Duration::from_secs(60)));
+        assert!(!entry("", old).is_fresh(Duration::from_secs(60)));
+        assert!(entry("", old).is_fresh(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn test_prune_removes_old_files() {
+        let temp = TempDir::new().unwrap();
+        let cache = ResponseCache::with_dir(temp.path().to_path_buf()).unwrap();
+        let listing = CacheKey::Listing("https://ifdb.org/search?json&searchfor=zork".to_string());
+        let details = CacheKey::Details("abc".to_string());
+
+        cache.put(&listing, &entry("zork", SystemTime::now())).unwrap();
+        cache.put(&details, &entry("{}", SystemTime::now())).unwrap();
+        cache.put_cover("abc", b"png").unwrap();
+
+        assert_eq!(cache.prune(Duration::from_secs(3600)).unwrap(), 0);
+        assert!(cache.get(&listing).is_some());
+
+        assert_eq!(cache.prune(Duration::ZERO).unwrap(), 3);
+        assert!(cache.get(&listing).is_none());
+        assert!(cache.get(&details).is_none());
+        assert!(!cache.cover_path("abc").exists());
+    }
+
+    #[test]
+    fn test_sanitize_key() {
+        assert_eq!(sanitize_key("abc-DEF_123"), "abc-DEF_123");
+        assert_eq!(sanitize_key("../etc/passwd"), "___etc_passwd");
+    }
+}
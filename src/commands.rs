@@ -0,0 +1,478 @@
+//! Non-interactive subcommands
+//!
+//! Each subcommand of `glkcli` is implemented here as a plain function that
+//! prints its results to stdout, keeping `main.rs` limited to argument parsing.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::config::GameFormat;
+use crate::detect::{Confidence, DetectionMethod};
+use crate::error::{DetectError, LaunchError};
+use crate::cache::ResponseCache;
+use crate::ifdb::{Game, IfdbClient, SearchOptions};
+use crate::inspect;
+use crate::integrity::{self, Verdict};
+use crate::launcher::Launcher;
+use crate::script::{self, ScriptOptions};
+use crate::settings::Settings;
+use crate::storage::{GameStorage, LocalGame, OrphanKind, PlaySession};
+use crate::transcript::strip_ansi;
+use crate::utils::{format_duration, format_size};
+
+/// Remove files in storage that no downloaded game references
+pub fn gc(dry_run: bool, keep_saves: bool) -> Result<()> {
+    let storage = GameStorage::new()?;
+
+    let orphans: Vec<_> = storage
+        .find_orphans()?
+        .into_iter()
+        .filter(|o| !(keep_saves && o.kind == OrphanKind::Saves))
+        .collect();
+
+    if orphans.is_empty() {
+        println!("Nothing to clean up");
+        return Ok(());
+    }
+
+    for orphan in &orphans {
+        println!(
+            "{:<6} {:>10}  {}",
+            orphan.kind,
+            format_size(orphan.size),
+            orphan.path.display()
+        );
+    }
+
+    let total: u64 = orphans.iter().map(|o| o.size).sum();
+
+    if dry_run {
+        println!("Would free {} ({} items)", format_size(total), orphans.len());
+    } else {
+        let freed = storage.remove_orphans(&orphans)?;
+        println!("Freed {} ({} items)", format_size(freed), orphans.len());
+    }
+
+    Ok(())
+}
+
+/// Play statistics for one game, as exported by `glkcli stats --json`
+#[derive(Serialize)]
+struct GameStats {
+    tuid: String,
+    title: String,
+    author: String,
+    play_count: u32,
+    total_seconds: u64,
+    last_played: Option<String>,
+    sessions: Vec<SessionStats>,
+}
+
+#[derive(Serialize)]
+struct SessionStats {
+    started_at: String,
+    ended_at: String,
+    duration_seconds: u64,
+    interpreter: String,
+    exit_code: Option<i32>,
+    saves_created: Vec<PathBuf>,
+}
+
+impl From<&LocalGame> for GameStats {
+    fn from(game: &LocalGame) -> Self {
+        GameStats {
+            tuid: game.tuid.clone(),
+            title: game.title.clone(),
+            author: game.author.clone(),
+            play_count: game.play_count,
+            total_seconds: game.total_play_time().as_secs(),
+            last_played: game.last_played.map(rfc3339),
+            sessions: game.sessions.iter().map(SessionStats::from).collect(),
+        }
+    }
+}
+
+impl From<&PlaySession> for SessionStats {
+    fn from(session: &PlaySession) -> Self {
+        SessionStats {
+            started_at: rfc3339(session.started_at),
+            ended_at: rfc3339(session.ended_at),
+            duration_seconds: session.duration_secs,
+            interpreter: session.interpreter.clone(),
+            exit_code: session.exit_code,
+            saves_created: session.saves_created.clone(),
+        }
+    }
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// Print play time and session history for downloaded games
+pub fn stats(json: bool) -> Result<()> {
+    let storage = GameStorage::new()?;
+
+    let mut games = storage.get_downloaded_games()?;
+    games.sort_by_key(|g| std::cmp::Reverse(g.total_play_time()));
+
+    if json {
+        let stats: Vec<GameStats> = games.iter().map(GameStats::from).collect();
+        let output = serde_json::to_string_pretty(&stats)
+            .context("Failed to serialize statistics")?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    if games.is_empty() {
+        println!("No downloaded games");
+        return Ok(());
+    }
+
+    println!("{:<40} {:>8} {:>12} {:>12}", "Title", "Sessions", "Time played", "Last session");
+    for game in &games {
+        let last = game
+            .last_session()
+            .map(|s| format_duration(s.duration()))
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "{:<40} {:>8} {:>12} {:>12}",
+            truncate(&game.title, 40),
+            game.sessions.len(),
+            format_duration(game.total_play_time()),
+            last
+        );
+    }
+
+    let total: std::time::Duration = games.iter().map(|g| g.total_play_time()).sum();
+    println!("\nTotal time played: {}", format_duration(total));
+
+    Ok(())
+}
+
+/// Re-check every downloaded game's file for damage or changes
+///
+/// Hashes are recorded for games added before they were kept. Any game that
+/// is missing, damaged or modified makes the command fail once all have been
+/// checked, so scripts can tell.
+pub fn verify() -> Result<()> {
+    let storage = GameStorage::new()?;
+
+    let mut games = storage.get_downloaded_games()?;
+    games.sort_by(|a, b| a.title.cmp(&b.title));
+
+    if games.is_empty() {
+        println!("No downloaded games");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for game in &games {
+        let status = match integrity::verify(game) {
+            Ok(Verdict::Ok) => "ok".to_string(),
+            Ok(Verdict::Unhashed(hash)) => {
+                storage.record_hash(&game.tuid, &hash)?;
+                "ok (hash recorded)".to_string()
+            }
+            Ok(Verdict::Missing) => {
+                failed += 1;
+                format!("MISSING {}", game.file_path.display())
+            }
+            Ok(Verdict::Damaged(check)) => {
+                failed += 1;
+                format!("DAMAGED ({})", check)
+            }
+            Ok(Verdict::Modified) => {
+                failed += 1;
+                "MODIFIED since it was downloaded".to_string()
+            }
+            Err(e) => {
+                failed += 1;
+                format!("ERROR {:#}", e)
+            }
+        };
+        println!("{:<40} {}", truncate(&game.title, 40), status);
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} games failed verification", failed, games.len());
+    }
+
+    println!("\nAll {} games verified", games.len());
+    Ok(())
+}
+
+/// Shorten text to at most `width` characters
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+
+    let mut short: String = text.chars().take(width.saturating_sub(1)).collect();
+    short.push('…');
+    short
+}
+
+/// Print a recorded transcript as plain text
+pub fn transcript(path: &Path) -> Result<()> {
+    let raw = std::fs::read(path)
+        .with_context(|| format!("Failed to read transcript: {}", path.display()))?;
+    print!("{}", strip_ansi(&raw));
+    Ok(())
+}
+
+/// Replay a command script into a game and check the output
+///
+/// The plain-text transcript is printed. With `expect`, it must match the
+/// expected transcript or an error describing the first difference is
+/// returned.
+pub fn run_script(
+    launcher: &Launcher,
+    game: &Path,
+    script_path: &Path,
+    expect: Option<&Path>,
+    options: &ScriptOptions,
+) -> Result<()> {
+    let commands = script::load_commands(script_path)?;
+    let outcome = launcher.run_script(game, &commands, options)?;
+
+    print!("{}", outcome.transcript);
+
+    if let Some(code) = outcome.exit_code.filter(|code| *code != 0) {
+        eprintln!("Interpreter exited with code {}", code);
+    }
+
+    if let Some(expect) = expect {
+        let expected = std::fs::read_to_string(expect)
+            .with_context(|| format!("Failed to read expected transcript: {}", expect.display()))?;
+
+        if let Some(difference) = script::compare_transcripts(&outcome.transcript, &expected) {
+            anyhow::bail!("Transcript does not match {} at {}", expect.display(), difference);
+        }
+    }
+
+    Ok(())
+}
+
+/// Detection result for one file, as printed by `--format --json` and `detect --json`
+#[derive(Serialize)]
+struct DetectReport {
+    file: PathBuf,
+    format: &'static str,
+    method: DetectionMethod,
+    confidence: Confidence,
+    interpreter: Option<&'static str>,
+    interpreter_found: bool,
+    /// Why the file could not be examined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl DetectReport {
+    fn new(launcher: &Launcher, file: &Path) -> Result<Self, DetectError> {
+        let detection = launcher.detect(file)?;
+        Ok(DetectReport {
+            file: file.to_path_buf(),
+            format: detection.format.name(),
+            method: detection.method,
+            confidence: detection.confidence,
+            interpreter: detection.format.interpreter(),
+            interpreter_found: launcher.interpreter_path(detection.format).is_some(),
+            error: None,
+        })
+    }
+
+    fn failed(file: &Path, error: &DetectError) -> Self {
+        DetectReport {
+            file: file.to_path_buf(),
+            format: GameFormat::Unknown.name(),
+            method: DetectionMethod::None,
+            confidence: Confidence::None,
+            interpreter: None,
+            interpreter_found: false,
+            error: Some(error.to_string()),
+        }
+    }
+
+    fn print(&self) {
+        if let Some(error) = &self.error {
+            println!("{}: {}", self.file.display(), error);
+            return;
+        }
+
+        let interpreter = match self.interpreter {
+            Some(name) if self.interpreter_found => name.to_string(),
+            Some(name) => format!("{} (not installed)", name),
+            None => "-".to_string(),
+        };
+        println!(
+            "{}: {} ({}, {} confidence) - {}",
+            self.file.display(),
+            self.format,
+            self.method,
+            self.confidence,
+            interpreter
+        );
+    }
+}
+
+/// Print the format of one game file, as `glkcli --format` does
+///
+/// A file in no known format is reported and then returned as an error, so
+/// the process exits with the unknown-format status.
+pub fn format(launcher: &Launcher, game: &Path, json: bool) -> Result<()> {
+    let report = DetectReport::new(launcher, game)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).context("Failed to serialize detection")?);
+    } else {
+        println!("Detected format: {}", report.format);
+    }
+
+    if report.format == GameFormat::Unknown.name() {
+        return Err(LaunchError::UnknownFormat(game.to_path_buf()).into());
+    }
+
+    Ok(())
+}
+
+/// Report the format of each file, searching directories recursively
+///
+/// Files that cannot be read are reported rather than stopping the run, but
+/// a path given on the command line that does not exist is an error.
+pub fn detect(paths: &[PathBuf], json: bool) -> Result<()> {
+    let launcher = Launcher::from_settings(&Settings::load().unwrap_or_default())?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(path, &mut files)?;
+        } else if path.exists() {
+            files.push(path.clone());
+        } else {
+            return Err(DetectError::NotFound(path.clone()).into());
+        }
+    }
+
+    let reports: Vec<DetectReport> = files
+        .iter()
+        .map(|file| DetectReport::new(&launcher, file).unwrap_or_else(|e| DetectReport::failed(file, &e)))
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports).context("Failed to serialize detection")?);
+    } else {
+        for report in &reports {
+            report.print();
+        }
+    }
+
+    Ok(())
+}
+
+/// Search IFDB and list the games found
+///
+/// Responses go through the same cache as the TUI, so a search repeated
+/// while offline is answered from it.
+pub async fn search(options: &SearchOptions, json: bool) -> Result<()> {
+    let mut client = IfdbClient::from_settings(&Settings::load().unwrap_or_default())?;
+    match ResponseCache::new() {
+        Ok(cache) => client = client.with_cache(cache),
+        Err(e) => log::warn!("IFDB response cache unavailable: {}", e),
+    }
+
+    let fetched = client.search_games(options).await?;
+    if fetched.stale {
+        eprintln!("Warning: IFDB could not be reached, showing cached results");
+    }
+
+    if json {
+        let hits: Vec<SearchHit> = fetched.data.iter().map(SearchHit::from).collect();
+        println!("{}", serde_json::to_string_pretty(&hits).context("Failed to serialize search results")?);
+        return Ok(());
+    }
+
+    if fetched.data.is_empty() {
+        println!("No games found");
+        return Ok(());
+    }
+
+    println!("{:<16} {:<40} {:<24} {:<12} {:>6}", "TUID", "Title", "Author", "System", "Rating");
+    for game in &fetched.data {
+        println!(
+            "{:<16} {:<40} {:<24} {:<12} {:>6}",
+            game.tuid,
+            truncate(&game.title, 40),
+            truncate(&game.author, 24),
+            truncate(game.devsys.as_deref().unwrap_or("-"), 12),
+            game.star_rating.map(|r| format!("{:.1}", r)).unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    Ok(())
+}
+
+/// A search result, as printed by `search --json`
+#[derive(Serialize)]
+struct SearchHit {
+    tuid: String,
+    title: String,
+    author: String,
+    system: Option<String>,
+    rating: Option<f64>,
+    link: String,
+}
+
+impl From<&Game> for SearchHit {
+    fn from(game: &Game) -> Self {
+        SearchHit {
+            tuid: game.tuid.clone(),
+            title: game.title.clone(),
+            author: game.author.clone(),
+            system: game.devsys.clone(),
+            rating: game.star_rating,
+            link: game.link.clone(),
+        }
+    }
+}
+
+/// Print the header fields of a story file
+pub fn inspect(path: &Path, json: bool) -> Result<()> {
+    let info = inspect::inspect(path)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info).context("Failed to serialize story info")?);
+        return Ok(());
+    }
+
+    let width = info.fields.iter().map(|f| f.name.len()).max().unwrap_or(0).max("Format".len());
+    println!("{:<width$}  {}", "Format", info.format, width = width);
+    for field in &info.fields {
+        println!("{:<width$}  {}", field.name, field.value, width = width);
+    }
+
+    Ok(())
+}
+
+/// All files below `dir`, skipping hidden entries, in name order
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| !path.file_name().and_then(|n| n.to_str()).unwrap_or(".").starts_with('.'))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum GameFormat {
     Unknown,
     ZCode,
@@ -19,9 +22,56 @@ pub enum GameFormat {
     Plus,
     Taylor,
     Advsys,
+    /// Web pages, and shortcuts to games played online
+    Web,
 }
 
 impl GameFormat {
+    /// Every format glkcli can launch, in the order menus list them
+    pub const PLAYABLE: [GameFormat; 17] = [
+        GameFormat::ZCode,
+        GameFormat::Glulx,
+        GameFormat::Tads,
+        GameFormat::Hugo,
+        GameFormat::Agt,
+        GameFormat::Jacl,
+        GameFormat::Level9,
+        GameFormat::Magnetic,
+        GameFormat::Alan2,
+        GameFormat::Alan3,
+        GameFormat::Adrift,
+        GameFormat::Adrift5,
+        GameFormat::Scott,
+        GameFormat::Plus,
+        GameFormat::Taylor,
+        GameFormat::Advsys,
+        GameFormat::Web,
+    ];
+
+    /// Short lowercase name used on the command line and in saved files
+    pub fn id(&self) -> &'static str {
+        match self {
+            GameFormat::Unknown => "unknown",
+            GameFormat::ZCode => "zcode",
+            GameFormat::Glulx => "glulx",
+            GameFormat::Tads => "tads",
+            GameFormat::Hugo => "hugo",
+            GameFormat::Agt => "agt",
+            GameFormat::Jacl => "jacl",
+            GameFormat::Level9 => "level9",
+            GameFormat::Magnetic => "magnetic",
+            GameFormat::Alan2 => "alan2",
+            GameFormat::Alan3 => "alan3",
+            GameFormat::Adrift => "adrift",
+            GameFormat::Adrift5 => "adrift5",
+            GameFormat::Scott => "scott",
+            GameFormat::Plus => "plus",
+            GameFormat::Taylor => "taylor",
+            GameFormat::Advsys => "advsys",
+            GameFormat::Web => "web",
+        }
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             GameFormat::Unknown => "Unknown",
@@ -41,9 +91,14 @@ impl GameFormat {
             GameFormat::Plus => "Plus",
             GameFormat::Taylor => "TaylorMade",
             GameFormat::Advsys => "AdvSys",
+            GameFormat::Web => "Web",
         }
     }
 
+    /// Name of the program that runs this format
+    ///
+    /// Web games are opened with the configured browser command, so they
+    /// only have a placeholder name here.
     pub fn interpreter(&self) -> Option<&'static str> {
         match self {
             GameFormat::Unknown => None,
@@ -63,14 +118,28 @@ impl GameFormat {
             GameFormat::Plus => Some("plus"),
             GameFormat::Taylor => Some("taylor"),
             GameFormat::Advsys => Some("advsys"),
+            GameFormat::Web => Some("browser"),
         }
     }
 
     pub fn flags(&self) -> &'static [&'static str] {
-        match self {
-            // Most interpreters don't need special flags
-            _ => &[],
-        }
+        // Most interpreters don't need special flags
+        &[]
+    }
+}
+
+/// Accepts a format's id (`zcode`) or display name (`Z-code`), in any case
+impl FromStr for GameFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GameFormat::PLAYABLE
+            .into_iter()
+            .find(|f| f.id().eq_ignore_ascii_case(s) || f.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let ids: Vec<&str> = GameFormat::PLAYABLE.iter().map(|f| f.id()).collect();
+                format!("unknown format '{}' (expected one of: {})", s, ids.join(", "))
+            })
     }
 }
 
@@ -82,31 +151,62 @@ impl fmt::Display for GameFormat {
 
 pub struct MagicPattern {
     pub pattern: &'static [u8],
+    /// Where in the file the pattern starts
+    pub offset: usize,
     pub format: GameFormat,
 }
 
 pub const MAGIC_PATTERNS: &[MagicPattern] = &[
     MagicPattern {
         pattern: b"Glul",
+        offset: 0,
         format: GameFormat::Glulx,
     },
     MagicPattern {
         pattern: b"TADS2 bin\x0A\x0D\x1A",
+        offset: 0,
         format: GameFormat::Tads,
     },
     MagicPattern {
         pattern: b"TADS3 r",
+        offset: 0,
+        format: GameFormat::Tads,
+    },
+    MagicPattern {
+        pattern: b"T3-image\x0D\x0A\x1A",
+        offset: 0,
         format: GameFormat::Tads,
     },
     MagicPattern {
-        pattern: b"Version ",
-        format: GameFormat::Adrift,
+        pattern: b"[InternetShortcut]",
+        offset: 0,
+        format: GameFormat::Web,
+    },
+    MagicPattern {
+        pattern: b"MaSc",
+        offset: 0,
+        format: GameFormat::Magnetic,
+    },
+    MagicPattern {
+        pattern: b"ALAN",
+        offset: 0,
+        format: GameFormat::Alan3,
     },
+    // AGX, the converted form of AGT games that agility reads
     MagicPattern {
-        pattern: b"\x3C\x42\x3F\xC9",
-        format: GameFormat::Adrift5,
+        pattern: b"\x58\xC7\xC1\x51",
+        offset: 0,
+        format: GameFormat::Agt,
     },
-    // Z-code is handled specially by version byte validation
+    // "ADVSYS", stored encoded as !c - 30 like the rest of the file
+    MagicPattern {
+        pattern: b"\xA0\x9D\x8B\x8E\x88\x8E",
+        offset: 2,
+        format: GameFormat::Advsys,
+    },
+    // Z-code, Hugo, Alan 2, Level 9, Scott Adams and JACL have no fixed
+    // signature and are validated structurally in `detect`. Adrift's header
+    // is obfuscated and decoded there too.
 ];
 
 pub struct ExtensionMapping {
@@ -139,13 +239,95 @@ pub const EXTENSION_MAPPINGS: &[ExtensionMapping] = &[
     ExtensionMapping { extension: "a3c", format: GameFormat::Alan3 },
     ExtensionMapping { extension: "taf", format: GameFormat::Adrift },
     ExtensionMapping { extension: "baf", format: GameFormat::Adrift5 },
-    ExtensionMapping { extension: "adrift", format: GameFormat::Adrift },
     ExtensionMapping { extension: "saga", format: GameFormat::Scott },
     ExtensionMapping { extension: "plus", format: GameFormat::Plus },
     ExtensionMapping { extension: "tay", format: GameFormat::Taylor },
     ExtensionMapping { extension: "advs", format: GameFormat::Advsys },
+    ExtensionMapping { extension: "html", format: GameFormat::Web },
+    ExtensionMapping { extension: "htm", format: GameFormat::Web },
+    ExtensionMapping { extension: "url", format: GameFormat::Web },
+];
+
+/// Which format games from an IFDB development system come in
+///
+/// IFDB names systems loosely ("Inform 7", "TADS 3", "ADRIFT 4"), so each
+/// pattern is matched as a substring of the lowercased name. More specific
+/// patterns come first.
+pub struct DevsysMapping {
+    pub pattern: &'static str,
+    pub format: GameFormat,
+}
+
+pub const DEVSYS_MAPPINGS: &[DevsysMapping] = &[
+    DevsysMapping { pattern: "inform 7", format: GameFormat::Glulx },
+    DevsysMapping { pattern: "inform", format: GameFormat::ZCode },
+    DevsysMapping { pattern: "dialog", format: GameFormat::ZCode },
+    DevsysMapping { pattern: "zil", format: GameFormat::ZCode },
+    DevsysMapping { pattern: "tads", format: GameFormat::Tads },
+    DevsysMapping { pattern: "adrift 5", format: GameFormat::Adrift5 },
+    DevsysMapping { pattern: "adrift", format: GameFormat::Adrift },
+    DevsysMapping { pattern: "hugo", format: GameFormat::Hugo },
+    DevsysMapping { pattern: "alan 3", format: GameFormat::Alan3 },
+    DevsysMapping { pattern: "alan", format: GameFormat::Alan2 },
+    DevsysMapping { pattern: "agt", format: GameFormat::Agt },
+    DevsysMapping { pattern: "jacl", format: GameFormat::Jacl },
+    DevsysMapping { pattern: "advsys", format: GameFormat::Advsys },
+    DevsysMapping { pattern: "scott adams", format: GameFormat::Scott },
+    DevsysMapping { pattern: "twine", format: GameFormat::Web },
+    DevsysMapping { pattern: "ink", format: GameFormat::Web },
+    DevsysMapping { pattern: "choicescript", format: GameFormat::Web },
+    DevsysMapping { pattern: "dendry", format: GameFormat::Web },
+    DevsysMapping { pattern: "windrift", format: GameFormat::Web },
+    DevsysMapping { pattern: "axma", format: GameFormat::Web },
+    DevsysMapping { pattern: "squiffy", format: GameFormat::Web },
+    DevsysMapping { pattern: "undum", format: GameFormat::Web },
+    DevsysMapping { pattern: "texture", format: GameFormat::Web },
+    DevsysMapping { pattern: "quest", format: GameFormat::Web },
+    DevsysMapping { pattern: "adventuron", format: GameFormat::Web },
+];
+
+/// The format games written with `devsys` come in, if glkcli can play it
+pub fn format_for_devsys(devsys: &str) -> Option<GameFormat> {
+    let devsys = devsys.to_lowercase();
+    DEVSYS_MAPPINGS
+        .iter()
+        .find(|mapping| devsys.contains(mapping.pattern))
+        .map(|mapping| mapping.format)
+}
+
+/// Which exit codes from an interpreter mean the game ended normally
+///
+/// Zero always does. Some of the older ports exit with a small non-zero code
+/// when the player quits; anything not listed here treats every non-zero code
+/// as a failure. Users can override the list per interpreter in the settings.
+pub struct ExitCodeRule {
+    pub interpreter: &'static str,
+    pub normal_codes: &'static [i32],
+}
+
+pub const EXIT_CODE_RULES: &[ExitCodeRule] = &[
+    ExitCodeRule { interpreter: "agility", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "advsys", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "alan2", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "hugo", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "jacl", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "level9", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "magnetic", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "scare", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "scott", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "plus", normal_codes: &[1, 2, 3] },
+    ExitCodeRule { interpreter: "taylor", normal_codes: &[1, 2, 3] },
 ];
 
+/// Non-zero exit codes that mean a normal end of play for `interpreter`
+pub fn normal_exit_codes(interpreter: &str) -> &'static [i32] {
+    EXIT_CODE_RULES
+        .iter()
+        .find(|rule| rule.interpreter == interpreter)
+        .map(|rule| rule.normal_codes)
+        .unwrap_or(&[])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +351,7 @@ mod tests {
         assert_eq!(GameFormat::Plus.name(), "Plus");
         assert_eq!(GameFormat::Taylor.name(), "TaylorMade");
         assert_eq!(GameFormat::Advsys.name(), "AdvSys");
+        assert_eq!(GameFormat::Web.name(), "Web");
     }
 
     #[test]
@@ -190,6 +373,21 @@ mod tests {
         assert_eq!(GameFormat::Plus.interpreter(), Some("plus"));
         assert_eq!(GameFormat::Taylor.interpreter(), Some("taylor"));
         assert_eq!(GameFormat::Advsys.interpreter(), Some("advsys"));
+        assert_eq!(GameFormat::Web.interpreter(), Some("browser"));
+    }
+
+    #[test]
+    fn test_game_format_from_str() {
+        assert_eq!("zcode".parse::<GameFormat>(), Ok(GameFormat::ZCode));
+        assert_eq!("Z-code".parse::<GameFormat>(), Ok(GameFormat::ZCode));
+        assert_eq!("ADRIFT5".parse::<GameFormat>(), Ok(GameFormat::Adrift5));
+        assert!("unknown".parse::<GameFormat>().is_err());
+        assert!("inform".parse::<GameFormat>().unwrap_err().contains("zcode, glulx"));
+
+        // Saved files use the same ids
+        for format in GameFormat::PLAYABLE {
+            assert_eq!(serde_json::to_string(&format).unwrap(), format!("\"{}\"", format.id()));
+        }
     }
 
     #[test]
@@ -214,6 +412,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_game_format_clone_copy() {
         let format1 = GameFormat::ZCode;
         let format2 = format1; // Copy
@@ -262,4 +461,40 @@ mod tests {
             assert_eq!(mapping.unwrap().format, GameFormat::ZCode);
         }
     }
+
+    #[test]
+    fn test_exit_code_rules() {
+        assert!(normal_exit_codes("bocfel").is_empty());
+        assert_eq!(normal_exit_codes("scare"), &[1, 2, 3]);
+
+        // Every rule should name an interpreter that some format uses
+        for rule in EXIT_CODE_RULES {
+            assert!(
+                [GameFormat::Hugo, GameFormat::Agt, GameFormat::Jacl, GameFormat::Level9,
+                 GameFormat::Magnetic, GameFormat::Alan2, GameFormat::Adrift, GameFormat::Scott,
+                 GameFormat::Plus, GameFormat::Taylor, GameFormat::Advsys]
+                    .iter()
+                    .any(|f| f.interpreter() == Some(rule.interpreter)),
+                "unknown interpreter {}",
+                rule.interpreter
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_for_devsys() {
+        assert_eq!(format_for_devsys("Inform 7"), Some(GameFormat::Glulx));
+        assert_eq!(format_for_devsys("Inform 6"), Some(GameFormat::ZCode));
+        assert_eq!(format_for_devsys("TADS 3"), Some(GameFormat::Tads));
+        assert_eq!(format_for_devsys("ADRIFT 5"), Some(GameFormat::Adrift5));
+        assert_eq!(format_for_devsys("ADRIFT 4"), Some(GameFormat::Adrift));
+        assert_eq!(format_for_devsys("Alan 3"), Some(GameFormat::Alan3));
+        assert_eq!(format_for_devsys("Twine"), Some(GameFormat::Web));
+        assert_eq!(format_for_devsys("ink"), Some(GameFormat::Web));
+        assert_eq!(format_for_devsys("Custom"), None);
+
+        for mapping in DEVSYS_MAPPINGS {
+            assert!(GameFormat::PLAYABLE.contains(&mapping.format));
+        }
+    }
 }
@@ -0,0 +1,481 @@
+//! Cover art rendering
+//!
+//! Covers are drawn with the best graphics protocol the terminal supports:
+//! kitty, iTerm2 or sixel for real images, and Unicode half blocks or ASCII
+//! shading everywhere else. The protocol can be forced with the
+//! `GLKCLI_COVER_ART` environment variable or the `cover_art` setting;
+//! `none` keeps the details views text-only.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageOutputFormat, RgbImage};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::Write as _;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Terminal cells are roughly twice as tall as they are wide
+const CELL_ASPECT: f64 = 2.0;
+
+/// Pixel size assumed for a cell when the terminal doesn't report one
+const DEFAULT_CELL_PIXELS: (u16, u16) = (10, 20);
+
+/// Brightness ramp used for ASCII rendering, darkest first
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// How cover art is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// kitty graphics protocol
+    Kitty,
+    /// iTerm2 inline images
+    Iterm2,
+    /// DEC sixel graphics
+    Sixel,
+    /// Unicode upper half blocks with 24-bit colours
+    HalfBlocks,
+    /// ASCII brightness ramp
+    Ascii,
+    /// Don't show cover art at all
+    None,
+}
+
+impl GraphicsProtocol {
+    /// Parse a protocol name; `auto` and unknown names return `None`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "kitty" => Some(GraphicsProtocol::Kitty),
+            "iterm2" | "iterm" => Some(GraphicsProtocol::Iterm2),
+            "sixel" => Some(GraphicsProtocol::Sixel),
+            "halfblocks" | "halfblock" | "blocks" => Some(GraphicsProtocol::HalfBlocks),
+            "ascii" => Some(GraphicsProtocol::Ascii),
+            "none" | "off" | "text" => Some(GraphicsProtocol::None),
+            _ => None,
+        }
+    }
+
+    /// Whether the protocol draws real images outside of ratatui's buffer
+    pub fn is_image_protocol(self) -> bool {
+        matches!(self, GraphicsProtocol::Kitty | GraphicsProtocol::Iterm2 | GraphicsProtocol::Sixel)
+    }
+}
+
+/// Choose the protocol to use for cover art
+///
+/// `GLKCLI_COVER_ART` wins over the configured value, and anything that is
+/// unset or `auto` falls back to detection from the environment.
+pub fn select_protocol(configured: Option<&str>) -> GraphicsProtocol {
+    env::var("GLKCLI_COVER_ART")
+        .ok()
+        .and_then(|name| GraphicsProtocol::parse(&name))
+        .or_else(|| configured.and_then(GraphicsProtocol::parse))
+        .unwrap_or_else(|| detect_from_env(|name| env::var(name).ok()))
+}
+
+/// Detect graphics support from terminal-identifying environment variables
+fn detect_from_env(var: impl Fn(&str) -> Option<String>) -> GraphicsProtocol {
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+
+    // Multiplexers don't pass graphics escapes through by default
+    if var("TMUX").is_some() || term.starts_with("screen") {
+        return text_fallback(&var);
+    }
+
+    if var("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || term_program == "ghostty" {
+        return GraphicsProtocol::Kitty;
+    }
+
+    if term_program == "iTerm.app"
+        || term_program == "WezTerm"
+        || var("LC_TERMINAL").as_deref() == Some("iTerm2")
+    {
+        return GraphicsProtocol::Iterm2;
+    }
+
+    if term.contains("sixel")
+        || term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || term.starts_with("yaft")
+        || term_program == "contour"
+    {
+        return GraphicsProtocol::Sixel;
+    }
+
+    text_fallback(&var)
+}
+
+/// Half blocks need a UTF-8 locale; fall back to ASCII otherwise
+fn text_fallback(var: &impl Fn(&str) -> Option<String>) -> GraphicsProtocol {
+    let locale = var("LC_ALL")
+        .filter(|v| !v.is_empty())
+        .or_else(|| var("LANG"))
+        .unwrap_or_default();
+
+    if locale.to_uppercase().contains("UTF") {
+        GraphicsProtocol::HalfBlocks
+    } else {
+        GraphicsProtocol::Ascii
+    }
+}
+
+/// Escape sequence that removes previously drawn images, if the protocol needs one
+pub fn clear_sequence(protocol: GraphicsProtocol) -> Option<&'static str> {
+    match protocol {
+        // kitty keeps images on their own layer until they are deleted
+        GraphicsProtocol::Kitty => Some("\x1b_Ga=d,d=A,q=2\x1b\\"),
+        _ => None,
+    }
+}
+
+/// Size of a terminal cell in pixels
+pub fn cell_pixel_size() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL_PIXELS,
+    }
+}
+
+/// A decoded cover image
+pub struct CoverImage {
+    pub path: PathBuf,
+    image: DynamicImage,
+}
+
+impl CoverImage {
+    /// Load and decode a cover image
+    pub fn open(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read cover art: {}", path.display()))?;
+
+        // Decode by content - cover files don't always have the right extension
+        let image = image::load_from_memory(&bytes)
+            .with_context(|| format!("Failed to decode cover art: {}", path.display()))?;
+
+        Ok(CoverImage {
+            path: path.to_path_buf(),
+            image,
+        })
+    }
+
+    /// Largest area inside `area` with the image's aspect ratio, centered
+    pub fn fit(&self, area: Rect) -> Rect {
+        let (width, height) = self.image.dimensions();
+        if width == 0 || height == 0 || area.width == 0 || area.height == 0 {
+            return Rect::new(area.x, area.y, 0, 0);
+        }
+
+        let aspect = width as f64 / height as f64;
+        let area_aspect = area.width as f64 / (area.height as f64 * CELL_ASPECT);
+
+        let (cols, rows) = if area_aspect > aspect {
+            let cols = (area.height as f64 * CELL_ASPECT * aspect).round() as u16;
+            (cols.clamp(1, area.width), area.height)
+        } else {
+            let rows = (area.width as f64 / aspect / CELL_ASPECT).round() as u16;
+            (area.width, rows.clamp(1, area.height))
+        };
+
+        Rect::new(
+            area.x + (area.width - cols) / 2,
+            area.y + (area.height - rows) / 2,
+            cols,
+            rows,
+        )
+    }
+
+    /// Render with upper half blocks: each cell shows two vertical pixels
+    pub fn halfblock_lines(&self, cols: u16, rows: u16) -> Vec<Line<'static>> {
+        if cols == 0 || rows == 0 {
+            return Vec::new();
+        }
+
+        let pixels = self.image
+            .resize_exact(cols as u32, rows as u32 * 2, FilterType::Triangle)
+            .to_rgb8();
+
+        (0..rows as u32)
+            .map(|row| {
+                let spans: Vec<Span<'static>> = (0..cols as u32)
+                    .map(|col| {
+                        let top = pixels.get_pixel(col, row * 2);
+                        let bottom = pixels.get_pixel(col, row * 2 + 1);
+                        Span::styled(
+                            "▀",
+                            Style::default()
+                                .fg(Color::Rgb(top[0], top[1], top[2]))
+                                .bg(Color::Rgb(bottom[0], bottom[1], bottom[2])),
+                        )
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Render with an ASCII brightness ramp
+    pub fn ascii_lines(&self, cols: u16, rows: u16) -> Vec<Line<'static>> {
+        if cols == 0 || rows == 0 {
+            return Vec::new();
+        }
+
+        let pixels = self.image
+            .resize_exact(cols as u32, rows as u32, FilterType::Triangle)
+            .to_luma8();
+
+        (0..rows as u32)
+            .map(|row| {
+                let text: String = (0..cols as u32)
+                    .map(|col| {
+                        let luma = pixels.get_pixel(col, row)[0] as usize;
+                        ASCII_RAMP[luma * ASCII_RAMP.len() / 256] as char
+                    })
+                    .collect();
+                Line::from(text)
+            })
+            .collect()
+    }
+
+    /// Escape sequence drawing the image over `area` with an image protocol
+    ///
+    /// The caller is responsible for moving the cursor to the top-left
+    /// corner of `area` first.
+    pub fn escape_sequence(&self, protocol: GraphicsProtocol, area: Rect, cell_pixels: (u16, u16)) -> Result<String> {
+        let width = (area.width as u32 * cell_pixels.0 as u32).max(1);
+        let height = (area.height as u32 * cell_pixels.1 as u32).max(1);
+        let scaled = self.image.resize_exact(width, height, FilterType::Triangle);
+
+        match protocol {
+            GraphicsProtocol::Kitty => Ok(kitty_sequence(&encode_png(&scaled)?, area.width, area.height)),
+            GraphicsProtocol::Iterm2 => Ok(iterm2_sequence(&encode_png(&scaled)?, area.width, area.height)),
+            GraphicsProtocol::Sixel => Ok(sixel_sequence(&scaled.to_rgb8())),
+            _ => Ok(String::new()),
+        }
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>> {
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, ImageOutputFormat::Png)
+        .context("Failed to encode cover art")?;
+    Ok(png.into_inner())
+}
+
+/// kitty graphics protocol: PNG data, base64 encoded, in chunks of 4096 bytes
+fn kitty_sequence(png: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        // Chunks of a base64 string are always valid UTF-8
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=100,c={},r={},C=1,q=2,m={};{}\x1b\\", cols, rows, more, chunk);
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    out
+}
+
+/// iTerm2 inline image protocol
+fn iterm2_sequence(png: &[u8], cols: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        cols,
+        rows,
+        base64::engine::general_purpose::STANDARD.encode(png)
+    )
+}
+
+/// Sixel graphics using a fixed 6x6x6 colour cube
+fn sixel_sequence(image: &RgbImage) -> String {
+    let (width, height) = image.dimensions();
+    let level = |c: u8| (c as u16 * 5 + 127) / 255;
+    let index = |x: u32, y: u32| {
+        let p = image.get_pixel(x, y);
+        (level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) as usize
+    };
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+
+    let used: BTreeSet<usize> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| index(x, y))
+        .collect();
+    for &color in &used {
+        let (r, g, b) = (color / 36, (color / 6) % 6, color % 6);
+        let _ = write!(out, "#{};2;{};{};{}", color, r * 20, g * 20, b * 20);
+    }
+
+    for band in (0..height).step_by(6) {
+        let rows = (height - band).min(6);
+        let band_colors: BTreeSet<usize> = (band..band + rows)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| index(x, y))
+            .collect();
+
+        for &color in &band_colors {
+            let _ = write!(out, "#{}", color);
+            let sixels: Vec<u8> = (0..width)
+                .map(|x| {
+                    let bits = (0..rows)
+                        .filter(|dy| index(x, band + dy) == color)
+                        .fold(0u8, |acc, dy| acc | (1 << dy));
+                    63 + bits
+                })
+                .collect();
+
+            // Run-length encode repeated sixels
+            let mut i = 0;
+            while i < sixels.len() {
+                let run = sixels[i..].iter().take_while(|&&s| s == sixels[i]).count();
+                if run > 3 {
+                    let _ = write!(out, "!{}{}", run, sixels[i] as char);
+                } else {
+                    out.extend(std::iter::repeat_n(sixels[i] as char, run));
+                }
+                i += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| map.get(name).cloned()
+    }
+
+    fn test_cover(width: u32, height: u32) -> CoverImage {
+        let image = RgbImage::from_fn(width, height, |x, _| {
+            if x < width / 2 { image::Rgb([255, 0, 0]) } else { image::Rgb([0, 0, 255]) }
+        });
+        CoverImage {
+            path: PathBuf::from("cover.png"),
+            image: DynamicImage::ImageRgb8(image),
+        }
+    }
+
+    #[test]
+    fn test_parse_protocol_names() {
+        assert_eq!(GraphicsProtocol::parse("kitty"), Some(GraphicsProtocol::Kitty));
+        assert_eq!(GraphicsProtocol::parse("iTerm2"), Some(GraphicsProtocol::Iterm2));
+        assert_eq!(GraphicsProtocol::parse("SIXEL"), Some(GraphicsProtocol::Sixel));
+        assert_eq!(GraphicsProtocol::parse("halfblocks"), Some(GraphicsProtocol::HalfBlocks));
+        assert_eq!(GraphicsProtocol::parse("ascii"), Some(GraphicsProtocol::Ascii));
+        assert_eq!(GraphicsProtocol::parse("none"), Some(GraphicsProtocol::None));
+        assert_eq!(GraphicsProtocol::parse("auto"), None);
+    }
+
+    #[test]
+    fn test_detect_kitty() {
+        let env = env_of(&[("TERM", "xterm-kitty")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::Kitty);
+    }
+
+    #[test]
+    fn test_detect_iterm2() {
+        let env = env_of(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "iTerm.app")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::Iterm2);
+    }
+
+    #[test]
+    fn test_detect_sixel() {
+        let env = env_of(&[("TERM", "foot")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::Sixel);
+    }
+
+    #[test]
+    fn test_detect_text_fallbacks() {
+        let env = env_of(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::HalfBlocks);
+
+        let env = env_of(&[("TERM", "vt100"), ("LANG", "C")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::Ascii);
+
+        // Images don't make it through tmux
+        let env = env_of(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(detect_from_env(env), GraphicsProtocol::HalfBlocks);
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_ratio() {
+        // Square image in a wide area: limited by height, two columns per row
+        let cover = test_cover(100, 100);
+        let fitted = cover.fit(Rect::new(0, 0, 80, 10));
+        assert_eq!((fitted.width, fitted.height), (20, 10));
+        assert_eq!(fitted.x, 30);
+
+        // Square image in a tall area: limited by width
+        let fitted = cover.fit(Rect::new(0, 0, 20, 40));
+        assert_eq!((fitted.width, fitted.height), (20, 10));
+    }
+
+    #[test]
+    fn test_halfblock_and_ascii_dimensions() {
+        let cover = test_cover(40, 40);
+
+        let lines = cover.halfblock_lines(8, 4);
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|l| l.spans.len() == 8));
+
+        let lines = cover.ascii_lines(8, 4);
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|l| l.width() == 8));
+    }
+
+    #[test]
+    fn test_sixel_sequence_structure() {
+        let cover = test_cover(4, 12);
+        let seq = sixel_sequence(&cover.image.to_rgb8());
+
+        assert!(seq.starts_with("\x1bPq\"1;1;4;12"));
+        assert!(seq.ends_with("\x1b\\"));
+        // Two bands of six rows
+        assert_eq!(seq.matches('-').count(), 2);
+        // Pure red and pure blue palette entries
+        assert!(seq.contains("#180;2;100;0;0"));
+        assert!(seq.contains("#5;2;0;0;100"));
+    }
+
+    #[test]
+    fn test_kitty_sequence_chunks() {
+        let data = vec![0u8; 10_000];
+        let seq = kitty_sequence(&data, 10, 5);
+
+        assert!(seq.starts_with("\x1b_Ga=T,f=100,c=10,r=5,C=1,q=2,m=1;"));
+        assert!(seq.contains("\x1b_Gm=0;"));
+        assert_eq!(seq.matches("\x1b_G").count(), 4);
+    }
+
+    #[test]
+    fn test_iterm2_sequence() {
+        let seq = iterm2_sequence(b"png", 10, 5);
+        assert!(seq.starts_with("\x1b]1337;File=inline=1;size=3;width=10;height=5"));
+        assert!(seq.ends_with(":cG5n\x07"));
+    }
+}
@@ -1,19 +1,114 @@
 use crate::config::*;
-use anyhow::{Context, Result};
+use crate::error::DetectError;
+use serde::Serialize;
+use std::fmt;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::Path;
 
-pub fn detect_format_by_header(file_path: &Path) -> Result<GameFormat> {
-    let mut file = File::open(file_path)
-        .with_context(|| format!("Failed to open file: {}", file_path.display()))?;
+type Result<T> = std::result::Result<T, DetectError>;
+
+/// How a game's format was worked out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DetectionMethod {
+    /// A signature at the start of the file
+    Header,
+    /// The executable chunk of a Blorb container
+    Blorb,
+    /// The file extension, because the contents were not recognised
+    Extension,
+    /// Nothing matched
+    None,
+}
+
+/// How much a detection result can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    None,
+    /// Only the extension or a guess about a container's contents
+    Low,
+    /// A loose check on a few header bytes that other files can pass
+    Medium,
+    /// An unambiguous signature
+    High,
+}
+
+impl fmt::Display for DetectionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DetectionMethod::Header => "header",
+            DetectionMethod::Blorb => "blorb",
+            DetectionMethod::Extension => "extension",
+            DetectionMethod::None => "none",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Confidence::None => "none",
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The detected format of a file and how it was arrived at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detection {
+    pub format: GameFormat,
+    pub method: DetectionMethod,
+    pub confidence: Confidence,
+}
+
+impl Detection {
+    fn new(format: GameFormat, method: DetectionMethod, confidence: Confidence) -> Self {
+        Detection { format, method, confidence }
+    }
+
+    fn unknown() -> Self {
+        Self::new(GameFormat::Unknown, DetectionMethod::None, Confidence::None)
+    }
+}
+
+/// Detect a file's format from its contents, falling back to its extension
+pub fn detect(file_path: &Path) -> Result<Detection> {
+    let detection = detect_by_header(file_path)?;
+    if detection.format != GameFormat::Unknown {
+        return Ok(detection);
+    }
+
+    match detect_format_by_extension(file_path) {
+        GameFormat::Unknown => Ok(Detection::unknown()),
+        format => Ok(Detection::new(format, DetectionMethod::Extension, Confidence::Low)),
+    }
+}
+
+fn detect_by_header(file_path: &Path) -> Result<Detection> {
+    use Confidence::*;
+    use DetectionMethod::Header;
+
+    let unreadable = |source| DetectError::Unreadable {
+        path: file_path.to_path_buf(),
+        source,
+    };
+
+    let mut file = File::open(file_path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => DetectError::NotFound(file_path.to_path_buf()),
+        _ => unreadable(e),
+    })?;
     
     let mut header = [0u8; 32];
-    let bytes_read = file.read(&mut header)
-        .context("Failed to read file header")?;
+    let bytes_read = file.read(&mut header).map_err(unreadable)?;
     
     if bytes_read < 4 {
-        return Ok(GameFormat::Unknown);
+        return Ok(Detection::unknown());
     }
     
     // Check for Blorb format first
@@ -23,50 +118,60 @@ pub fn detect_format_by_header(file_path: &Path) -> Result<GameFormat> {
     
     // Check magic patterns
     for pattern in MAGIC_PATTERNS {
-        if bytes_read >= pattern.pattern.len() && &header[..pattern.pattern.len()] == pattern.pattern {
-            return Ok(pattern.format);
+        let end = pattern.offset + pattern.pattern.len();
+        if bytes_read >= end && &header[pattern.offset..end] == pattern.pattern {
+            return Ok(Detection::new(pattern.format, Header, High));
         }
     }
     
-    // Special handling for Z-code
-    if header[0] >= 1 && header[0] <= 8 && bytes_read >= 26 {
-        // Additional Z-code validation could go here
-        return Ok(GameFormat::ZCode);
+    // Adrift TAF files start with an obfuscated version string
+    if let Some(format) = taf_format(&header[..bytes_read]) {
+        return Ok(Detection::new(format, Header, High));
     }
     
-    // Special handling for Hugo
-    if bytes_read >= 7 && header[3] == b'-' && header[6] == b'-' {
-        return Ok(GameFormat::Hugo);
-    }
+    let file_len = file.metadata().map_err(unreadable)?.len();
     
-    // Additional validation for Adrift TAF files
-    if bytes_read >= 8 && &header[0..8] == b"Version " {
-        // Look for version numbers like "3.9" or "4.0" following "Version "
-        if bytes_read >= 12 {
-            let version_part = std::str::from_utf8(&header[8..12]).unwrap_or("");
-            if version_part.starts_with("3.9") || version_part.starts_with("4.0") {
-                return Ok(GameFormat::Adrift);
-            }
+    // Z-code has no signature, so check the header describes a plausible story
+    if (1..=8).contains(&header[0]) {
+        if let Some(confidence) = validate_zcode(&mut file, 0, file_len).map_err(unreadable)? {
+            return Ok(Detection::new(GameFormat::ZCode, Header, confidence));
         }
-        // Default to Adrift if we found "Version " but couldn't parse version
-        return Ok(GameFormat::Adrift);
     }
     
-    // Special handling for ZIP files with .adrift extension
-    if bytes_read >= 4 && &header[0..4] == b"PK\x03\x04" {
-        let extension = file_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase());
-        
-        if let Some(ext) = extension {
-            if ext == "adrift" {
-                return Ok(GameFormat::Adrift);
-            }
-        }
+    // The remaining formats are recognised by their structure
+    file.seek(SeekFrom::Start(0)).map_err(unreadable)?;
+    let mut data = Vec::new();
+    file.take(SCAN_LIMIT).read_to_end(&mut data).map_err(unreadable)?;
+    
+    if is_hugo(&data, file_len) {
+        return Ok(Detection::new(GameFormat::Hugo, Header, Medium));
+    }
+    
+    if is_alan2(&data, file_len) {
+        return Ok(Detection::new(GameFormat::Alan2, Header, High));
     }
     
-    Ok(GameFormat::Unknown)
+    if is_level9(&data, file_len) {
+        return Ok(Detection::new(GameFormat::Level9, Header, Medium));
+    }
+    
+    if is_scott_adams(&data) {
+        return Ok(Detection::new(GameFormat::Scott, Header, Medium));
+    }
+    
+    if is_html(&data) {
+        return Ok(Detection::new(GameFormat::Web, Header, Medium));
+    }
+    
+    if let Some(confidence) = jacl_confidence(&data) {
+        return Ok(Detection::new(GameFormat::Jacl, Header, confidence));
+    }
+    
+    if is_agt_descriptions(file_path, &data, file_len) {
+        return Ok(Detection::new(GameFormat::Agt, Header, Medium));
+    }
+    
+    Ok(Detection::unknown())
 }
 
 pub fn detect_format_by_extension(file_path: &Path) -> GameFormat {
@@ -86,60 +191,472 @@ pub fn detect_format_by_extension(file_path: &Path) -> GameFormat {
     GameFormat::Unknown
 }
 
-fn detect_format_by_blorb(file_path: &Path) -> Result<GameFormat> {
-    let mut file = File::open(file_path)
-        .with_context(|| format!("Failed to open Blorb file: {}", file_path.display()))?;
+fn detect_format_by_blorb(file_path: &Path) -> Result<Detection> {
+    let unreadable = |source| DetectError::Unreadable {
+        path: file_path.to_path_buf(),
+        source,
+    };
+
+    let mut file = File::open(file_path).map_err(unreadable)?;
     
     // Skip FORM header and look for RIdx
-    file.seek(SeekFrom::Start(12))
-        .context("Failed to seek in Blorb file")?;
+    file.seek(SeekFrom::Start(12)).map_err(unreadable)?;
     
     let mut ridx = [0u8; 4];
-    file.read_exact(&mut ridx)
-        .context("Failed to read RIdx header")?;
+    file.read_exact(&mut ridx).map_err(unreadable)?;
     
     if &ridx != b"RIdx" {
-        return Ok(GameFormat::Unknown);
+        return Ok(Detection::unknown());
     }
     
-    // Read RIdx size
-    let mut size_bytes = [0u8; 4];
-    file.read_exact(&mut size_bytes)
-        .context("Failed to read RIdx size")?;
-    
-    // Skip resource count (4 bytes) and read first resource entry
-    file.seek(SeekFrom::Current(4))
-        .context("Failed to seek past resource count")?;
-    
-    // Read first resource type (4 bytes)
-    let mut resource_type = [0u8; 4];
-    if file.read_exact(&mut resource_type).is_ok() && &resource_type == b"Exec" {
-        // Skip resource number (4 bytes), read offset (4 bytes)
-        file.seek(SeekFrom::Current(4))
-            .context("Failed to seek past resource number")?;
-        
-        let mut offset_bytes = [0u8; 4];
-        if file.read_exact(&mut offset_bytes).is_ok() {
-            let offset = u32::from_be_bytes(offset_bytes) as u64;
-            
-            // Jump to the executable data and check its format
-            file.seek(SeekFrom::Start(offset))
-                .context("Failed to seek to executable data")?;
-            
-            let mut exec_header = [0u8; 4];
-            if file.read_exact(&mut exec_header).is_ok() {
-                if &exec_header == b"Glul" || &exec_header == b"GLUL" {
-                    return Ok(GameFormat::Glulx);
-                } else if exec_header[0] >= 1 && exec_header[0] <= 8 {
-                    // Z-code version
-                    return Ok(GameFormat::ZCode);
-                }
+    if let Some(exec) = blorb_exec(&mut file).map_err(unreadable)? {
+        match &exec.kind {
+            b"GLUL" => {
+                return Ok(Detection::new(GameFormat::Glulx, DetectionMethod::Blorb, Confidence::High));
             }
+            b"ADRI" => {
+                return Ok(Detection::new(GameFormat::Adrift5, DetectionMethod::Blorb, Confidence::High));
+            }
+            b"ZCOD" => {
+                return match validate_zcode(&mut file, exec.start, exec.len).map_err(unreadable)? {
+                    Some(confidence) => Ok(Detection::new(GameFormat::ZCode, DetectionMethod::Blorb, confidence)),
+                    None => Ok(Detection::unknown()),
+                };
+            }
+            _ => {}
         }
     }
     
     // Fallback: assume Z-code as it's most common in Blorb files
-    Ok(GameFormat::ZCode)
+    Ok(Detection::new(GameFormat::ZCode, DetectionMethod::Blorb, Confidence::Low))
+}
+
+/// Where the story file sits inside a Blorb
+pub(crate) struct BlorbChunk {
+    /// Chunk type, such as `ZCOD` or `GLUL`
+    pub kind: [u8; 4],
+    /// Offset of the story data from the start of the file
+    pub start: u64,
+    pub len: u64,
+}
+
+/// Find the executable chunk of a Blorb through its resource index
+pub(crate) fn blorb_exec<R: Read + Seek>(reader: &mut R) -> io::Result<Option<BlorbChunk>> {
+    let mut index = [0u8; 12];
+    reader.seek(SeekFrom::Start(12))?;
+    reader.read_exact(&mut index)?;
+    if &index[0..4] != b"RIdx" {
+        return Ok(None);
+    }
+    let count = u32::from_be_bytes([index[8], index[9], index[10], index[11]]);
+    
+    // Each entry is a usage, a resource number and the offset of its chunk
+    let mut exec_offset = None;
+    for _ in 0..count {
+        let mut entry = [0u8; 12];
+        if reader.read_exact(&mut entry).is_err() {
+            break;
+        }
+        if &entry[0..4] == b"Exec" {
+            exec_offset = Some(u32::from_be_bytes([entry[8], entry[9], entry[10], entry[11]]) as u64);
+            break;
+        }
+    }
+    let Some(offset) = exec_offset else {
+        return Ok(None);
+    };
+    
+    // The offset points at the chunk header: a type and a length
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut chunk = [0u8; 8];
+    if reader.read_exact(&mut chunk).is_err() {
+        return Ok(None);
+    }
+    
+    // Some tools point the index straight at the story data
+    if &chunk[0..4] == b"Glul" {
+        return Ok(Some(BlorbChunk {
+            kind: *b"GLUL",
+            start: offset,
+            len: file_len - offset,
+        }));
+    }
+    
+    Ok(Some(BlorbChunk {
+        kind: [chunk[0], chunk[1], chunk[2], chunk[3]],
+        start: offset + 8,
+        len: u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64,
+    }))
+}
+
+/// Where the story data sits in a file that may be a Blorb
+pub(crate) struct StoryExtent {
+    pub start: u64,
+    pub len: u64,
+    /// Whether the story is the executable chunk of a Blorb
+    pub in_blorb: bool,
+}
+
+/// Find the story in a file: a Blorb's executable chunk, or the whole file
+pub(crate) fn story_extent<R: Read + Seek>(reader: &mut R) -> io::Result<StoryExtent> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+
+    let mut form = [0u8; 12];
+    reader.seek(SeekFrom::Start(0))?;
+    if reader.read_exact(&mut form).is_ok() && &form[0..4] == b"FORM" && &form[8..12] == b"IFRS" {
+        if let Some(exec) = blorb_exec(reader)? {
+            return Ok(StoryExtent {
+                start: exec.start,
+                len: exec.len.min(file_len.saturating_sub(exec.start)),
+                in_blorb: true,
+            });
+        }
+    }
+
+    Ok(StoryExtent { start: 0, len: file_len, in_blorb: false })
+}
+
+/// Size of the Z-machine header, which is also the least dynamic memory a story can have
+const ZCODE_HEADER_LEN: u64 = 64;
+
+/// Check that `len` bytes starting at `start` look like a Z-code story
+///
+/// The header's table addresses must point inside the story, dynamic memory
+/// must end before high memory starts, and the length field (version 3 and
+/// later) must fit in the data available. Returns `None` if any of that
+/// fails. A story whose checksum also matches is reported with high
+/// confidence; without a checksum, or with a wrong one as patched games often
+/// have, the result is medium.
+fn validate_zcode<R: Read + Seek>(reader: &mut R, start: u64, len: u64) -> io::Result<Option<Confidence>> {
+    if len < ZCODE_HEADER_LEN {
+        return Ok(None);
+    }
+
+    let mut header = [0u8; ZCODE_HEADER_LEN as usize];
+    reader.seek(SeekFrom::Start(start))?;
+    if let Err(e) = reader.read_exact(&mut header) {
+        return match e.kind() {
+            io::ErrorKind::UnexpectedEof => Ok(None),
+            _ => Err(e),
+        };
+    }
+
+    let version = header[0];
+    let word = |at: usize| u16::from_be_bytes([header[at], header[at + 1]]) as u64;
+    let high_memory = word(0x04);
+    let initial_pc = word(0x06);
+    let dictionary = word(0x08);
+    let objects = word(0x0A);
+    let globals = word(0x0C);
+    let static_memory = word(0x0E);
+
+    // Story length in the header, counted in units that grow with the version
+    let multiplier = match version {
+        1..=3 => 2,
+        4 | 5 => 4,
+        6..=8 => 8,
+        _ => return Ok(None),
+    };
+    let stated_len = if version >= 3 { word(0x1A) * multiplier } else { 0 };
+    if stated_len > len {
+        return Ok(None);
+    }
+    let story_len = if stated_len > 0 { stated_len } else { len };
+
+    let in_story = |addr: u64| (ZCODE_HEADER_LEN..story_len).contains(&addr);
+    let in_dynamic = |addr: u64| (ZCODE_HEADER_LEN..static_memory).contains(&addr);
+
+    let valid = static_memory >= ZCODE_HEADER_LEN
+        && static_memory <= high_memory
+        && high_memory < story_len
+        && in_story(dictionary)
+        && in_dynamic(objects)
+        && in_dynamic(globals)
+        // Version 6 stores a packed routine address here instead
+        && (version == 6 || in_story(initial_pc));
+    if !valid {
+        return Ok(None);
+    }
+
+    if stated_len == 0 {
+        return Ok(Some(Confidence::Medium));
+    }
+
+    // The checksum is the sum of every byte after the header, modulo 0x10000
+    let mut body = Vec::with_capacity((story_len - ZCODE_HEADER_LEN) as usize);
+    reader.take(story_len - ZCODE_HEADER_LEN).read_to_end(&mut body)?;
+    let checksum = body.iter().fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+
+    if checksum as u64 == word(0x1C) {
+        Ok(Some(Confidence::High))
+    } else {
+        Ok(Some(Confidence::Medium))
+    }
+}
+
+/// "Version 4.00" as it appears at the start of an Adrift 4 TAF file
+const TAF_V400_SIGNATURE: [u8; 12] = [0x3c, 0x42, 0x3f, 0xc9, 0x6a, 0x87, 0xc2, 0xcf, 0x93, 0x45, 0x3e, 0x61];
+
+/// Work out the Adrift version from the start of a TAF file
+///
+/// TAF files are XORed with the output of Visual Basic's random number
+/// generator from a fixed seed, so the key for the first bytes is the same in
+/// every file and can be recovered from a known header. Versions 3.80, 3.90
+/// and 4.00 are Adrift 4 era files; 5.x headers come from Adrift 5.
+fn taf_format(header: &[u8]) -> Option<GameFormat> {
+    if header.len() < TAF_V400_SIGNATURE.len() {
+        return None;
+    }
+
+    let plain: Vec<u8> = header
+        .iter()
+        .zip(TAF_V400_SIGNATURE.iter().zip(b"Version 4.00"))
+        .map(|(byte, (signature, text))| byte ^ signature ^ text)
+        .collect();
+
+    match plain.strip_prefix(b"Version ")? {
+        b"3.80" | b"3.90" | b"4.00" => Some(GameFormat::Adrift),
+        [b'5', b'.', minor @ ..] if minor.iter().all(u8::is_ascii_digit) => Some(GameFormat::Adrift5),
+        _ => None,
+    }
+}
+
+/// Most of a file read for the structural checks
+///
+/// Every format checked this way is far smaller; a file that is larger can
+/// only match the checks that look at its start.
+const SCAN_LIMIT: u64 = 1024 * 1024;
+
+/// Hugo: a "MM-DD-YY" serial number and a table of addresses in the file
+///
+/// The addresses are stored divided by 4, or by 16 from version 3.4 on.
+fn is_hugo(data: &[u8], file_len: u64) -> bool {
+    if data.len() < 0x29 {
+        return false;
+    }
+
+    let serial_ok = data[3..11].iter().enumerate().all(|(i, &c)| match i {
+        2 | 5 => c == b'-',
+        _ => c.is_ascii_digit(),
+    });
+    if !serial_ok {
+        return false;
+    }
+
+    let scale = if data[0] < 34 { 4 } else { 16 };
+    (0x0B..0x29)
+        .step_by(2)
+        .all(|at| u16::from_le_bytes([data[at], data[at + 1]]) as u64 * scale <= file_len)
+}
+
+/// Alan 2: the file size in words, table addresses within it and a checksum
+fn is_alan2(data: &[u8], file_len: u64) -> bool {
+    if data.len() < 160 || data.len() as u64 != file_len {
+        return false;
+    }
+
+    let word = |at: usize| u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let words = (file_len / 4) as u32;
+    if word(4) != words || (24..=80).step_by(4).any(|at| word(at) > words) {
+        return false;
+    }
+
+    let checksum = data[160..].iter().fold(0u32, |sum, &b| sum.wrapping_add(b as u32));
+    checksum == word(152)
+}
+
+/// Size of the area level9 keeps for list pointers, starting at 0x8000
+const LEVEL9_LIST_AREA: usize = 0x800;
+
+/// Sizes of 48K and 128K ZX Spectrum snapshots
+const SNAPSHOT_SIZES: &[usize] = &[49179, 131103, 147487];
+
+/// Level 9 version 3 and 4 game data
+///
+/// Data files start with the game block. Spectrum snapshots hold it
+/// somewhere in memory, so for files of a snapshot's size every offset is
+/// tried, as level9 itself does.
+fn is_level9(data: &[u8], file_len: u64) -> bool {
+    if data.len() as u64 != file_len {
+        return false;
+    }
+
+    // Running byte sums make the checksum of any block a subtraction
+    let mut sums = Vec::with_capacity(data.len() + 1);
+    sums.push(0u8);
+    for &b in data {
+        sums.push(sums[sums.len() - 1].wrapping_add(b));
+    }
+
+    if is_level9_block(data, &sums, 0) {
+        return true;
+    }
+
+    SNAPSHOT_SIZES.contains(&data.len()) && (1..data.len()).any(|start| is_level9_block(data, &sums, start))
+}
+
+fn is_level9_block(data: &[u8], sums: &[u8], start: usize) -> bool {
+    let len = data.len();
+    if start + 0x12 + 24 > len {
+        return false;
+    }
+
+    let word = |at: usize| u16::from_le_bytes([data[start + at], data[start + at + 1]]) as usize;
+
+    // The first word is the block length less one, and its bytes sum to zero
+    let size = word(0) + 1;
+    if size < 0x12 + 24 || start + size > len || sums[start + size] != sums[start] {
+        return false;
+    }
+
+    let (messages, messages_len) = (word(0x2), word(0x4));
+    let (dictionary, dictionary_len) = (word(0xA), word(0xC));
+    if messages == 0 || messages_len == 0 || start + messages + messages_len > len {
+        return false;
+    }
+    if dictionary == 0 || dictionary_len == 0 || start + dictionary + dictionary_len * 4 > len {
+        return false;
+    }
+
+    // Twelve table pointers follow: offsets into the data, or into the list area
+    let list_area = 0x8000..0x8000 + LEVEL9_LIST_AREA;
+    for j in 0..12 {
+        let pointer = word(0x12 + j * 2);
+        if j != 11 && (0x8000..0x9000).contains(&pointer) {
+            if !list_area.contains(&pointer) {
+                return false;
+            }
+        } else if start + pointer >= len {
+            return false;
+        }
+    }
+
+    list_area.contains(&word(0x12 + 10 * 2))
+}
+
+/// Scott Adams (ScottFree) text data: a header of twelve numbers, one per line
+fn is_scott_adams(data: &[u8]) -> bool {
+    let numbers: Option<Vec<i64>> = data
+        .split(|&b| b == b'\n')
+        .take(12)
+        .map(|line| std::str::from_utf8(line).ok()?.trim().parse().ok())
+        .collect();
+
+    let Some([_, items, actions, words, rooms, max_carry, player_room, treasures, word_len, light_time, messages, treasure_room]) =
+        numbers.as_deref().and_then(|n| <[i64; 12]>::try_from(n).ok())
+    else {
+        return false;
+    };
+
+    (0..=500).contains(&items)
+        && (0..=1000).contains(&actions)
+        && (0..=500).contains(&words)
+        && (0..=500).contains(&rooms)
+        && (-1..=500).contains(&max_carry)
+        && (0..=rooms).contains(&player_room)
+        && (0..=items).contains(&treasures)
+        && (1..=10).contains(&word_len)
+        && (-1..=32767).contains(&light_time)
+        && (0..=500).contains(&messages)
+        && (0..=rooms).contains(&treasure_room)
+}
+
+/// JACL source: a `#!` line naming jacl, or function and world definitions
+fn jacl_confidence(data: &[u8]) -> Option<Confidence> {
+    let text = &data[..data.len().min(4096)];
+    if text.contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(text);
+    let first = text.lines().next()?;
+    if first.starts_with("#!") && first.contains("jacl") {
+        return Some(Confidence::High);
+    }
+
+    let lines = || text.lines().map(str::trim_start);
+    let functions = lines().any(|l| l.starts_with("{+"));
+    let world = lines().any(|l| ["location ", "object ", "grammar "].iter().any(|k| l.starts_with(k)));
+
+    (functions && world).then_some(Confidence::Medium)
+}
+
+/// Size of a record in an AGT description (`.d$$`) file
+const AGT_RECORD_SIZE: u64 = 81;
+
+/// Classic AGT game: the description file of a `.d$$`/`.da1` pair
+///
+/// Descriptions are stored as fixed records of a length byte followed by up
+/// to 80 bytes of encoded text. agility also needs the `.da1` file beside it,
+/// which holds the game header as lines of text.
+fn is_agt_descriptions(file_path: &Path, data: &[u8], file_len: u64) -> bool {
+    if file_len == 0 || file_len % AGT_RECORD_SIZE != 0 {
+        return false;
+    }
+
+    let records_valid = data
+        .chunks_exact(AGT_RECORD_SIZE as usize)
+        .all(|record| (record[0] as u64) < AGT_RECORD_SIZE);
+    if !records_valid {
+        return false;
+    }
+
+    ["da1", "DA1"].iter().any(|ext| {
+        let mut header = Vec::new();
+        File::open(file_path.with_extension(ext))
+            .and_then(|f| f.take(256).read_to_end(&mut header))
+            .map(|len| len > 0 && header.iter().all(|&b| b.is_ascii_graphic() || b.is_ascii_whitespace()))
+            .unwrap_or(false)
+    })
+}
+
+/// A web page: a doctype or `<html>` tag, after any byte order mark,
+/// whitespace and comments
+fn is_html(data: &[u8]) -> bool {
+    let mut text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    loop {
+        text = text.trim_ascii_start();
+        let Some(comment) = text.strip_prefix(b"<!--") else {
+            break;
+        };
+        match comment.windows(3).position(|w| w == b"-->") {
+            Some(end) => text = &comment[end + 3..],
+            None => return false,
+        }
+    }
+
+    let starts_with = |prefix: &[u8]| {
+        text.len() >= prefix.len() && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+    };
+    starts_with(b"<!doctype html") || starts_with(b"<html")
+}
+
+/// A minimal story that passes [`validate_zcode`], with a correct checksum
+#[cfg(test)]
+pub(crate) fn test_story(version: u8) -> Vec<u8> {
+    let multiplier = match version {
+        1..=3 => 2,
+        4 | 5 => 4,
+        _ => 8,
+    };
+    let mut data = vec![0u8; 1024];
+    data[0] = version;
+    let mut set = |at: usize, value: u16| data[at..at + 2].copy_from_slice(&value.to_be_bytes());
+    set(0x04, 0x200); // high memory
+    set(0x06, 0x201); // initial PC
+    set(0x08, 0x100); // dictionary
+    set(0x0A, 0x080); // objects
+    set(0x0C, 0x0C0); // globals
+    set(0x0E, 0x100); // static memory
+    if version >= 3 {
+        set(0x1A, (1024 / multiplier) as u16);
+    }
+    data[0x300] = 0xB0; // quit
+    data[0x301] = 0x0B;
+    let checksum = data[64..].iter().fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+    data[0x1C..0x1E].copy_from_slice(&checksum.to_be_bytes());
+    data
 }
 
 #[cfg(test)]
@@ -155,12 +672,13 @@ mod tests {
         file
     }
 
+    fn detect_format_by_header(file_path: &Path) -> Result<GameFormat> {
+        Ok(detect_by_header(file_path)?.format)
+    }
+
     #[test]
     fn test_detect_zcode_by_header() {
-        // Z-code version 5 header (minimal valid header)
-        let mut data = vec![0u8; 32];
-        data[0] = 5; // Z-code version 5
-        let file = create_test_file(&data);
+        let file = create_test_file(&test_story(5));
         
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::ZCode);
@@ -169,15 +687,52 @@ mod tests {
     #[test]
     fn test_detect_zcode_all_versions() {
         for version in 1..=8 {
-            let mut data = vec![0u8; 32];
-            data[0] = version;
-            let file = create_test_file(&data);
+            let file = create_test_file(&test_story(version));
             
             let format = detect_format_by_header(file.path()).unwrap();
             assert_eq!(format, GameFormat::ZCode, "Version {} should be detected", version);
         }
     }
 
+    #[test]
+    fn test_zcode_rejects_implausible_headers() {
+        // A version byte alone is not enough
+        let mut data = vec![0u8; 1024];
+        data[0] = 5;
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+
+        // Static memory after high memory
+        let mut data = test_story(5);
+        data[0x0E..0x10].copy_from_slice(&0x280u16.to_be_bytes());
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+
+        // Header length longer than the file
+        let data = &test_story(5)[..512];
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+
+        // Object table outside dynamic memory
+        let mut data = test_story(3);
+        data[0x0A..0x0C].copy_from_slice(&0x180u16.to_be_bytes());
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_zcode_checksum_sets_confidence() {
+        let detection = detect(create_test_file(&test_story(5)).path()).unwrap();
+        assert_eq!(detection.confidence, Confidence::High);
+
+        // A patched story still runs, but is less certain to be Z-code
+        let mut data = test_story(5);
+        data[0x3FF] = 0x42;
+        let detection = detect(create_test_file(&data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::ZCode);
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        // Versions 1 and 2 have no length or checksum
+        let detection = detect(create_test_file(&test_story(2)).path()).unwrap();
+        assert_eq!(detection.confidence, Confidence::Medium);
+    }
+
     #[test]
     fn test_detect_glulx_by_header() {
         let mut data = vec![0u8; 32];
@@ -206,37 +761,228 @@ mod tests {
         
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::Tads);
+
+        let mut data = vec![0u8; 80];
+        data[0..11].copy_from_slice(b"T3-image\x0D\x0A\x1A");
+        let format = detect_format_by_header(create_test_file(&data).path()).unwrap();
+        assert_eq!(format, GameFormat::Tads);
     }
 
     #[test]
     fn test_detect_hugo_by_header() {
-        let mut data = vec![0u8; 32];
-        data[3] = b'-';
-        data[6] = b'-';
-        let file = create_test_file(&data);
+        let file = create_test_file(&hugo_file());
         
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::Hugo);
+
+        // Addresses beyond the end of the file
+        let data = &hugo_file()[..0x60];
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+
+        // Dashes alone are not a serial number
+        let mut data = vec![0u8; 64];
+        data[5] = b'-';
+        data[8] = b'-';
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    fn hugo_file() -> Vec<u8> {
+        let mut data = vec![0u8; 0x100];
+        data[0] = 31; // Hugo 3.1
+        data[1..3].copy_from_slice(b"HG");
+        data[3..11].copy_from_slice(b"04-01-05");
+        for at in (0x0B..0x29).step_by(2) {
+            data[at] = 0x20; // 0x80 once scaled
+        }
+        data
+    }
+
+    #[test]
+    fn test_detect_signatures() {
+        let signature = |offset: usize, magic: &[u8]| {
+            let mut data = vec![0u8; 64];
+            data[offset..offset + magic.len()].copy_from_slice(magic);
+            detect_format_by_header(create_test_file(&data).path()).unwrap()
+        };
+
+        assert_eq!(signature(0, b"MaSc"), GameFormat::Magnetic);
+        assert_eq!(signature(0, b"ALAN\x03\x00"), GameFormat::Alan3);
+        assert_eq!(signature(0, b"\x58\xC7\xC1\x51"), GameFormat::Agt);
+        assert_eq!(signature(2, b"\xA0\x9D\x8B\x8E\x88\x8E"), GameFormat::Advsys);
+        assert_eq!(signature(0, b"ADVSYS"), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_alan2() {
+        let mut data = vec![0u8; 256];
+        data[4..8].copy_from_slice(&64u32.to_be_bytes()); // size in words
+        for at in (24..=80).step_by(4) {
+            data[at..at + 4].copy_from_slice(&40u32.to_be_bytes());
+        }
+        for (i, b) in data[160..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let checksum: u32 = data[160..].iter().map(|&b| b as u32).sum();
+        data[152..156].copy_from_slice(&checksum.to_be_bytes());
+
+        let detection = detect(create_test_file(&data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Alan2);
+        assert_eq!(detection.confidence, Confidence::High);
+
+        data[200] ^= 0xFF;
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    /// A Level 9 game block of `len` bytes whose bytes sum to zero
+    fn level9_block(len: usize) -> Vec<u8> {
+        let mut data = vec![0u8; len];
+        let mut set = |at: usize, value: u16| data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+        set(0x0, (len - 1) as u16);
+        set(0x2, 0x40); // messages
+        set(0x4, 0x10);
+        set(0xA, 0x60); // dictionary
+        set(0xC, 0x10);
+        for j in 0..12 {
+            set(0x12 + j * 2, if j == 10 { 0x8000 } else { 0x100 });
+        }
+        let sum = data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        data[len - 1] = 0u8.wrapping_sub(sum);
+        data
+    }
+
+    #[test]
+    fn test_detect_level9() {
+        let detection = detect(create_test_file(&level9_block(0x200)).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Level9);
+        assert_eq!(detection.method, DetectionMethod::Header);
+
+        // Inside a 48K Spectrum snapshot
+        let mut snapshot = vec![0u8; 49179];
+        snapshot[27 + 0x1000..27 + 0x1200].copy_from_slice(&level9_block(0x200));
+        assert_eq!(detect_format_by_header(create_test_file(&snapshot).path()).unwrap(), GameFormat::Level9);
+
+        // The same block elsewhere in an arbitrary file is not searched for
+        let mut data = vec![0u8; 0x1000];
+        data[0x400..0x600].copy_from_slice(&level9_block(0x200));
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+
+        let mut data = level9_block(0x200);
+        data[0x100] ^= 0x01;
+        assert_eq!(detect_format_by_header(create_test_file(&data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_scott_adams() {
+        let data = b" 4736\n 65\n 170\n 69\n 33\n 6\n 1\n 13\n 3\n 125\n 75\n 3\n 150 0 0 0 0 0 0 0\n";
+        let detection = detect(create_test_file(data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Scott);
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        // Player starts in a room that does not exist
+        let data = b" 4736\n 65\n 170\n 69\n 33\n 6\n 99\n 13\n 3\n 125\n 75\n 3\n";
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+
+        let data = b"1,2,3\n4,5,6\n";
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_jacl() {
+        let data = b"#!../bin/jacl\n\nconstant GAME_VERSION 1\n";
+        let detection = detect(create_test_file(data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Jacl);
+        assert_eq!(detection.confidence, Confidence::High);
+
+        let data = b"{+intro\nwrite \"Welcome\"\n}\n\nlocation kitchen : kitchen\n";
+        let detection = detect(create_test_file(data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Jacl);
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        let data = b"location of the party: the kitchen\n";
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_agt_descriptions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let descriptions = dir.path().join("CRUSADE.D$$");
+        let mut data = vec![0u8; 81 * 3];
+        data[0] = 12;
+        data[81] = 80;
+        std::fs::write(&descriptions, &data).unwrap();
+
+        // Without the .da1 file agility can't run it
+        assert_eq!(detect_format_by_header(&descriptions).unwrap(), GameFormat::Unknown);
+
+        std::fs::write(dir.path().join("CRUSADE.DA1"), b"1\r\n12\r\n0\r\n").unwrap();
+        let detection = detect(&descriptions).unwrap();
+        assert_eq!(detection.format, GameFormat::Agt);
+        assert_eq!(detection.method, DetectionMethod::Header);
+
+        // Records hold at most 80 bytes of text
+        data[162] = 81;
+        std::fs::write(&descriptions, &data).unwrap();
+        assert_eq!(detect_format_by_header(&descriptions).unwrap(), GameFormat::Unknown);
+
+        std::fs::write(&descriptions, &data[..100]).unwrap();
+        assert_eq!(detect_format_by_header(&descriptions).unwrap(), GameFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_web() {
+        let data = b"\xEF\xBB\xBF\n<!-- Twine 2 -->\n<!DOCTYPE html>\n<html><tw-storydata></tw-storydata></html>";
+        let detection = detect(create_test_file(data).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Web);
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        let data = b"[InternetShortcut]\r\nURL=https://example.com/\r\n";
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Web);
+
+        let data = b"<!-- unterminated <html>";
+        assert_eq!(detect_format_by_header(create_test_file(data).path()).unwrap(), GameFormat::Unknown);
+    }
+
+    /// The start of a TAF file whose header decodes to `version`
+    fn taf_header(version: &[u8; 12]) -> Vec<u8> {
+        let mut data = vec![0u8; 32];
+        for (i, byte) in version.iter().enumerate() {
+            data[i] = byte ^ TAF_V400_SIGNATURE[i] ^ b"Version 4.00"[i];
+        }
+        data
     }
 
     #[test]
     fn test_detect_adrift_by_header() {
         let mut data = vec![0u8; 32];
-        data[0..12].copy_from_slice(b"Version 3.9 ");
+        data[0..12].copy_from_slice(&[0x3c, 0x42, 0x3f, 0xc9, 0x6a, 0x87, 0xc2, 0xcf, 0x94, 0x45, 0x37, 0x61]);
         let file = create_test_file(&data);
         
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::Adrift);
+
+        for version in [b"Version 3.80", b"Version 4.00"] {
+            let file = create_test_file(&taf_header(version));
+            assert_eq!(detect_format_by_header(file.path()).unwrap(), GameFormat::Adrift);
+        }
+
+        // An unsupported version, and a plain text file, are not claimed
+        let file = create_test_file(&taf_header(b"Version 2.00"));
+        assert_eq!(detect_format_by_header(file.path()).unwrap(), GameFormat::Unknown);
+        let file = create_test_file(b"Version 1.2 release notes\n\nFixed crash on startup.\n");
+        assert_eq!(detect_format_by_header(file.path()).unwrap(), GameFormat::Unknown);
     }
 
     #[test]
     fn test_detect_adrift5_by_header() {
-        let mut data = vec![0u8; 32];
-        data[0..4].copy_from_slice(b"\x3C\x42\x3F\xC9");
-        let file = create_test_file(&data);
+        let file = create_test_file(&taf_header(b"Version 5.00"));
         
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::Adrift5);
+
+        let file = create_test_file(&blorb_with_exec(b"ADRI", &[0x78, 0x9c, 0, 0]));
+        let detection = detect(file.path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Adrift5);
+        assert_eq!(detection.method, DetectionMethod::Blorb);
     }
 
     #[test]
@@ -285,7 +1031,7 @@ mod tests {
     #[test]
     fn test_detect_format_by_extension_adrift() {
         assert_eq!(detect_format_by_extension(Path::new("game.taf")), GameFormat::Adrift);
-        assert_eq!(detect_format_by_extension(Path::new("game.adrift")), GameFormat::Adrift);
+        assert_eq!(detect_format_by_extension(Path::new("game.adrift")), GameFormat::Unknown);
     }
 
     #[test]
@@ -298,7 +1044,7 @@ mod tests {
     #[test]
     fn test_detect_format_nonexistent_file() {
         let result = detect_format_by_header(Path::new("/nonexistent/file.z5"));
-        assert!(result.is_err());
+        assert!(matches!(result, Err(DetectError::NotFound(_))));
     }
 
     #[test]
@@ -319,10 +1065,72 @@ mod tests {
         let file = create_test_file(&data);
         let format = detect_format_by_header(file.path()).unwrap();
         assert_eq!(format, GameFormat::Glulx);
+
+        let detection = detect(file.path()).unwrap();
+        assert_eq!(detection.method, DetectionMethod::Blorb);
+        assert_eq!(detection.confidence, Confidence::High);
+    }
+
+    /// A Blorb whose index lists a picture before the executable chunk
+    fn blorb_with_exec(chunk_type: &[u8; 4], story: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(b"FORM");
+        data[8..12].copy_from_slice(b"IFRS");
+        data[12..16].copy_from_slice(b"RIdx");
+        data[16..20].copy_from_slice(&28u32.to_be_bytes());
+        data[20..24].copy_from_slice(&2u32.to_be_bytes());
+        data[24..28].copy_from_slice(b"Pict");
+        data[36..40].copy_from_slice(b"Exec");
+        data[44..48].copy_from_slice(&64u32.to_be_bytes());
+        data.extend_from_slice(chunk_type);
+        data.extend_from_slice(&(story.len() as u32).to_be_bytes());
+        data.extend_from_slice(story);
+        data
+    }
+
+    #[test]
+    fn test_detect_blorb_zcode_chunk() {
+        let file = create_test_file(&blorb_with_exec(b"ZCOD", &test_story(8)));
+        let detection = detect(file.path()).unwrap();
+        assert_eq!(detection.format, GameFormat::ZCode);
+        assert_eq!(detection.method, DetectionMethod::Blorb);
+        assert_eq!(detection.confidence, Confidence::High);
+
+        // The chunk length bounds the story, not the file
+        let mut story = test_story(8);
+        story.truncate(900);
+        let file = create_test_file(&blorb_with_exec(b"ZCOD", &story));
+        assert_eq!(detect(file.path()).unwrap().format, GameFormat::Unknown);
+
+        let file = create_test_file(&blorb_with_exec(b"GLUL", b"Glul\0\x03\x01\x03"));
+        assert_eq!(detect(file.path()).unwrap().format, GameFormat::Glulx);
+    }
+
+    #[test]
+    fn test_detection_method_and_confidence() {
+        let detection = detect(create_test_file(&test_story(5)).path()).unwrap();
+        assert_eq!(detection.method, DetectionMethod::Header);
+        assert_eq!(detection.confidence, Confidence::High);
+
+        let detection = detect(create_test_file(&hugo_file()).path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Hugo);
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        let mut file = NamedTempFile::with_suffix(".ulx").unwrap();
+        file.write_all(&[0xFF; 32]).unwrap();
+        file.flush().unwrap();
+        let detection = detect(file.path()).unwrap();
+        assert_eq!(detection.format, GameFormat::Glulx);
+        assert_eq!(detection.method, DetectionMethod::Extension);
+        assert_eq!(detection.confidence, Confidence::Low);
+
+        let detection = detect(create_test_file(&[0xFF; 32]).path()).unwrap();
+        assert_eq!(detection, Detection::unknown());
     }
 
     #[test]
     fn test_detect_zip_with_adrift_extension() {
+        // scare cannot open archives, so a zipped game is not playable as it is
         let mut data = vec![0u8; 32];
         data[0..4].copy_from_slice(b"PK\x03\x04"); // ZIP signature
         
@@ -330,7 +1138,7 @@ mod tests {
         file.write_all(&data).expect("Failed to write test data");
         file.flush().expect("Failed to flush file");
         
-        let format = detect_format_by_header(file.path()).unwrap();
-        assert_eq!(format, GameFormat::Adrift);
+        let format = detect(file.path()).unwrap().format;
+        assert_eq!(format, GameFormat::Unknown);
     }
 }
@@ -0,0 +1,213 @@
+//! Error types for failures callers need to tell apart
+//!
+//! Most of glkcli reports errors through `anyhow`, which is fine when the
+//! only thing to do with an error is show it. Launching a game is different:
+//! the TUI wants to suggest a fix and the CLI wants a distinct exit status
+//! depending on whether the file was missing, the format unknown or the
+//! interpreter absent. These enums carry that distinction. When they pass
+//! through `anyhow` they can be recovered with `downcast_ref`.
+
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::hooks::HookEvent;
+
+use crate::config::GameFormat;
+
+/// Failure to read a game file while working out its format
+#[derive(Debug, Error)]
+pub enum DetectError {
+    #[error("Game file does not exist: {}", .0.display())]
+    NotFound(PathBuf),
+
+    #[error("Failed to read game file: {}", .path.display())]
+    Unreadable {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Failure to start a game or to run it to a normal exit
+#[derive(Debug, Error)]
+pub enum LaunchError {
+    #[error(transparent)]
+    Detect(#[from] DetectError),
+
+    #[error("Unable to detect game format: {}", .0.display())]
+    UnknownFormat(PathBuf),
+
+    #[error("No interpreter configured for format: {0}")]
+    NoInterpreter(GameFormat),
+
+    #[error("Interpreter '{interpreter}' not found")]
+    InterpreterNotFound {
+        interpreter: String,
+        format: GameFormat,
+    },
+
+    #[error("Failed to execute interpreter: {}", .path.display())]
+    Spawn {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("{interpreter} exited with error code: {code}")]
+    ExitCode { interpreter: String, code: i32 },
+
+    #[error("Interpreter was terminated by signal")]
+    Signaled,
+
+    #[error("Interpreter was stopped after reaching the time limit")]
+    TimedOut,
+
+    #[error("{event} hook failed{}: {command}", .code.map(|c| format!(" with code {}", c)).unwrap_or_default())]
+    HookFailed { event: HookEvent, command: String, code: Option<i32> },
+
+    #[error("{event} hook could not be started: {command}")]
+    HookSpawn {
+        event: HookEvent,
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// Problems running the session itself, such as recording a transcript
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl LaunchError {
+    /// A suggestion the user can act on, if there is an obvious one
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            LaunchError::Detect(DetectError::NotFound(_)) => {
+                Some("the game file has gone - delete the game and download it again".to_string())
+            }
+            LaunchError::UnknownFormat(_) | LaunchError::NoInterpreter(_) => {
+                Some("this file is not a format glkcli can play".to_string())
+            }
+            LaunchError::InterpreterNotFound { interpreter, format } => Some(format!(
+                "install {} (the {} interpreter) or add it to PATH",
+                interpreter,
+                format.name()
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Exit status for failures not covered below
+pub const EXIT_FAILURE: u8 = 1;
+/// Invalid command-line arguments
+pub const EXIT_USAGE: u8 = 2;
+/// The game file is missing or cannot be read
+pub const EXIT_FILE_NOT_FOUND: u8 = 3;
+/// The game file is not in a format glkcli can play
+pub const EXIT_UNKNOWN_FORMAT: u8 = 4;
+/// The interpreter for the game's format is not installed or won't start
+pub const EXIT_INTERPRETER_MISSING: u8 = 5;
+/// The interpreter exited with an error, was killed or ran out of time
+pub const EXIT_INTERPRETER_FAILED: u8 = 6;
+
+/// Process exit status for an error that ended a command-line run
+///
+/// These values are documented in the README and relied on by scripts, so
+/// existing ones must not change.
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    if let Some(err) = err.downcast_ref::<DetectError>() {
+        return detect_exit_code(err);
+    }
+
+    match err.downcast_ref::<LaunchError>() {
+        Some(LaunchError::Detect(err)) => detect_exit_code(err),
+        Some(LaunchError::UnknownFormat(_) | LaunchError::NoInterpreter(_)) => EXIT_UNKNOWN_FORMAT,
+        Some(LaunchError::InterpreterNotFound { .. } | LaunchError::Spawn { .. }) => {
+            EXIT_INTERPRETER_MISSING
+        }
+        Some(LaunchError::ExitCode { .. } | LaunchError::Signaled | LaunchError::TimedOut) => {
+            EXIT_INTERPRETER_FAILED
+        }
+        Some(LaunchError::HookFailed { .. } | LaunchError::HookSpawn { .. } | LaunchError::Other(_)) | None => {
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn detect_exit_code(err: &DetectError) -> u8 {
+    match err {
+        DetectError::NotFound(_) | DetectError::Unreadable { .. } => EXIT_FILE_NOT_FOUND,
+    }
+}
+
+/// Library operations that can fail for reasons other than I/O
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Game {0} is not in the library")]
+    GameNotFound(String),
+
+    #[error("No trash entry named {0}")]
+    TrashEntryNotFound(String),
+
+    #[error("'{0}' has been downloaded again")]
+    AlreadyInLibrary(String),
+
+    #[error("Cannot restore over {}", .0.display())]
+    RestoreConflict(PathBuf),
+
+    #[error("No interactive fiction file found in ZIP archive")]
+    NoGameInArchive,
+
+    #[error("Failed to parse metadata file {}", .path.display())]
+    CorruptMetadata {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_error_through_anyhow() {
+        let err: anyhow::Error = LaunchError::InterpreterNotFound {
+            interpreter: "bocfel".to_string(),
+            format: GameFormat::ZCode,
+        }
+        .into();
+        let err = err.context("Failed to run game");
+
+        let launch = err.downcast_ref::<LaunchError>().unwrap();
+        assert!(matches!(launch, LaunchError::InterpreterNotFound { .. }));
+        assert!(launch.hint().unwrap().contains("install bocfel"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let code = |err: LaunchError| exit_code(&anyhow::Error::from(err).context("Failed to run game"));
+
+        assert_eq!(code(DetectError::NotFound(PathBuf::from("zork.z5")).into()), EXIT_FILE_NOT_FOUND);
+        assert_eq!(code(LaunchError::UnknownFormat(PathBuf::from("notes.txt"))), EXIT_UNKNOWN_FORMAT);
+        assert_eq!(code(LaunchError::TimedOut), EXIT_INTERPRETER_FAILED);
+        let hook_spawn = LaunchError::HookSpawn {
+            event: HookEvent::PreLaunch,
+            command: "mpc pause".to_string(),
+            source: io::Error::from(io::ErrorKind::NotFound),
+        };
+        assert_eq!(hook_spawn.to_string(), "Pre-launch hook could not be started: mpc pause");
+        assert_eq!(code(hook_spawn), EXIT_FAILURE);
+        assert_eq!(code(LaunchError::Other(anyhow::anyhow!("pty closed"))), EXIT_FAILURE);
+        assert_eq!(exit_code(&anyhow::anyhow!("no network")), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_detect_error_is_transparent() {
+        let err = LaunchError::from(DetectError::NotFound(PathBuf::from("/tmp/zork.z5")));
+        assert_eq!(err.to_string(), "Game file does not exist: /tmp/zork.z5");
+        assert!(err.hint().is_some());
+    }
+}
@@ -0,0 +1,137 @@
+//! Formats chosen by hand for files detection cannot identify
+//!
+//! When glkcli cannot tell what a game file is, the TUI asks the player to
+//! pick a format and launches with that. The choice is kept in
+//! `~/.glkcli/formats.json`, keyed by the file's path, and is used again for
+//! as long as the file's SHA-256 matches - a file replaced by something else
+//! goes back through detection.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::GameFormat;
+use crate::integrity::sha256_file;
+
+/// A format picked for one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatChoice {
+    pub format: GameFormat,
+    /// Interpreter to run instead of the format's default
+    #[serde(default)]
+    pub interpreter: Option<String>,
+    /// SHA-256 of the file when the choice was made
+    pub sha256: String,
+}
+
+/// Every remembered choice, and where they are saved
+#[derive(Debug, Clone, Default)]
+pub struct FormatChoices {
+    /// File the choices are written to; `None` keeps them in memory only
+    path: Option<PathBuf>,
+    choices: HashMap<PathBuf, FormatChoice>,
+}
+
+impl FormatChoices {
+    /// Location of the choices file
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine home directory"))?
+            .join(".glkcli")
+            .join("formats.json"))
+    }
+
+    /// Load the choices saved in `~/.glkcli/formats.json`
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load choices from a specific file, starting empty if it doesn't exist
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let choices = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read format choices: {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse format choices: {}", path.display()))?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(FormatChoices {
+            path: Some(path.to_path_buf()),
+            choices,
+        })
+    }
+
+    /// The choice made for a file, if the file has not changed since
+    pub fn get(&self, file: &Path) -> Option<&FormatChoice> {
+        let choice = self.choices.get(&Self::key(file))?;
+        let hash = sha256_file(file).ok()?;
+        (choice.sha256 == hash).then_some(choice)
+    }
+
+    /// Remember the format to use for a file and save all choices
+    pub fn remember(&mut self, file: &Path, format: GameFormat, interpreter: Option<String>) -> Result<()> {
+        let choice = FormatChoice {
+            format,
+            interpreter,
+            sha256: sha256_file(file)?,
+        };
+        self.choices.insert(Self::key(file), choice);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create glkcli directory")?;
+        }
+        let content = serde_json::to_string_pretty(&self.choices)
+            .context("Failed to serialize format choices")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write format choices: {}", path.display()))
+    }
+
+    /// Choices are keyed by absolute path so relative launches find them
+    fn key(file: &Path) -> PathBuf {
+        file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remember_and_reload() {
+        let temp = TempDir::new().unwrap();
+        let game = temp.path().join("mystery.bin");
+        fs::write(&game, b"not a known format").unwrap();
+        let path = temp.path().join("formats.json");
+
+        let mut choices = FormatChoices::load_from(&path).unwrap();
+        assert!(choices.get(&game).is_none());
+        choices.remember(&game, GameFormat::Glulx, Some("glulxe".to_string())).unwrap();
+
+        let reloaded = FormatChoices::load_from(&path).unwrap();
+        let choice = reloaded.get(&game).unwrap();
+        assert_eq!(choice.format, GameFormat::Glulx);
+        assert_eq!(choice.interpreter.as_deref(), Some("glulxe"));
+    }
+
+    #[test]
+    fn test_choice_forgotten_when_file_changes() {
+        let temp = TempDir::new().unwrap();
+        let game = temp.path().join("mystery.bin");
+        fs::write(&game, b"first version").unwrap();
+
+        let mut choices = FormatChoices::default();
+        choices.remember(&game, GameFormat::Hugo, None).unwrap();
+        assert!(choices.get(&game).is_some());
+
+        fs::write(&game, b"second version").unwrap();
+        assert!(choices.get(&game).is_none());
+    }
+}
@@ -0,0 +1,227 @@
+//! User commands run around each game launch
+//!
+//! Hooks are shell commands configured in the settings (for every game) or
+//! in a game's launch options (for that game only). Pre-launch hooks run
+//! before the interpreter starts and post-launch hooks after it exits, with
+//! global hooks first. Each runs through `sh -c` in the game's directory,
+//! attached to the terminal, with `GLKCLI_*` variables describing the game.
+//!
+//! A failing pre-launch hook stops the launch, so a hook can refuse to start
+//! a game. Once pre-launch hooks have run, post-launch hooks always run too,
+//! even if the interpreter could not be started. A failing post-launch hook
+//! is only logged, since the session has already happened.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::config::GameFormat;
+use crate::error::LaunchError;
+
+/// Commands to run before and after a game
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre_launch: Vec<String>,
+    pub post_launch: Vec<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_launch.is_empty() && self.post_launch.is_empty()
+    }
+
+    /// Add another set of hooks to run after these
+    pub fn extend(&mut self, other: &Hooks) {
+        self.pre_launch.extend(other.pre_launch.iter().cloned());
+        self.post_launch.extend(other.post_launch.iter().cloned());
+    }
+}
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreLaunch,
+    PostLaunch,
+}
+
+impl HookEvent {
+    /// Value of `GLKCLI_HOOK`, matching the settings key
+    pub fn id(&self) -> &'static str {
+        match self {
+            HookEvent::PreLaunch => "pre_launch",
+            HookEvent::PostLaunch => "post_launch",
+        }
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            HookEvent::PreLaunch => "Pre-launch",
+            HookEvent::PostLaunch => "Post-launch",
+        })
+    }
+}
+
+/// The library entry a launch belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookGame {
+    pub tuid: String,
+    pub save_dir: PathBuf,
+}
+
+/// What hooks are told about a launch
+#[derive(Debug, Clone)]
+pub struct HookContext<'a> {
+    /// Absolute path of the game file
+    pub game_path: &'a Path,
+    pub format: GameFormat,
+    pub game: Option<&'a HookGame>,
+    /// Set for post-launch hooks; `exit_code` is `None` if the interpreter
+    /// was killed or never started
+    pub exit: Option<HookExit<'a>>,
+}
+
+/// How the interpreter finished, for post-launch hooks
+#[derive(Debug, Clone)]
+pub struct HookExit<'a> {
+    pub interpreter: &'a str,
+    pub exit_code: Option<i32>,
+    pub duration: Duration,
+}
+
+impl HookContext<'_> {
+    /// Environment variables passed to every hook command
+    pub fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let game_dir = self.game_path.parent().unwrap_or(Path::new("."));
+        let save_dir = self.game.map(|g| g.save_dir.as_path()).unwrap_or(game_dir);
+
+        let mut env = vec![
+            ("GLKCLI_HOOK", event.id().to_string()),
+            ("GLKCLI_GAME_PATH", self.game_path.display().to_string()),
+            ("GLKCLI_GAME_FORMAT", self.format.id().to_string()),
+            ("GLKCLI_SAVE_DIR", save_dir.display().to_string()),
+            ("GLKCLI_GAME_TUID", self.game.map(|g| g.tuid.clone()).unwrap_or_default()),
+        ];
+        if let Some(exit) = &self.exit {
+            env.push(("GLKCLI_INTERPRETER", exit.interpreter.to_string()));
+            env.push((
+                "GLKCLI_EXIT_CODE",
+                exit.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+            ));
+            env.push(("GLKCLI_DURATION_SECS", exit.duration.as_secs().to_string()));
+        }
+        env
+    }
+}
+
+/// Run pre-launch hooks in order, stopping at the first that fails
+pub fn run_pre_launch(hooks: &Hooks, context: &HookContext) -> Result<(), LaunchError> {
+    for command in &hooks.pre_launch {
+        run(command, HookEvent::PreLaunch, context)?;
+    }
+    Ok(())
+}
+
+/// Run every post-launch hook, logging any that fail
+pub fn run_post_launch(hooks: &Hooks, context: &HookContext) {
+    for command in &hooks.post_launch {
+        if let Err(e) = run(command, HookEvent::PostLaunch, context) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+fn run(command: &str, event: HookEvent, context: &HookContext) -> Result<(), LaunchError> {
+    let mut cmd = Command::new("/bin/sh");
+    cmd.arg("-c").arg(command).envs(context.env(event));
+    if let Some(dir) = context.game_path.parent() {
+        cmd.current_dir(dir);
+    }
+
+    let status = cmd.status().map_err(|source| LaunchError::HookSpawn {
+        event,
+        command: command.to_string(),
+        source,
+    })?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LaunchError::HookFailed {
+            event,
+            command: command.to_string(),
+            code: status.code(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hooks_see_game_variables() {
+        let temp = TempDir::new().unwrap();
+        let game_path = temp.path().join("zork.z5");
+        fs::write(&game_path, b"").unwrap();
+        let game = HookGame {
+            tuid: "0dbnusxunq7fw5ro".to_string(),
+            save_dir: temp.path().join("saves"),
+        };
+        let hooks = Hooks {
+            pre_launch: vec!["echo \"$GLKCLI_HOOK $GLKCLI_GAME_TUID $GLKCLI_GAME_FORMAT\" > pre.txt".to_string()],
+            post_launch: vec!["echo \"$GLKCLI_EXIT_CODE $GLKCLI_INTERPRETER\" > post.txt".to_string()],
+        };
+
+        let mut context = HookContext {
+            game_path: &game_path,
+            format: GameFormat::ZCode,
+            game: Some(&game),
+            exit: None,
+        };
+        run_pre_launch(&hooks, &context).unwrap();
+        context.exit = Some(HookExit {
+            interpreter: "bocfel",
+            exit_code: Some(0),
+            duration: Duration::from_secs(5),
+        });
+        run_post_launch(&hooks, &context);
+
+        assert_eq!(
+            fs::read_to_string(temp.path().join("pre.txt")).unwrap(),
+            "pre_launch 0dbnusxunq7fw5ro zcode\n"
+        );
+        assert_eq!(fs::read_to_string(temp.path().join("post.txt")).unwrap(), "0 bocfel\n");
+    }
+
+    #[test]
+    fn test_failing_pre_launch_hook_stops() {
+        let temp = TempDir::new().unwrap();
+        let game_path = temp.path().join("zork.z5");
+        let hooks = Hooks {
+            pre_launch: vec!["exit 3".to_string(), "touch ran.txt".to_string()],
+            ..Hooks::default()
+        };
+        let context = HookContext {
+            game_path: &game_path,
+            format: GameFormat::ZCode,
+            game: None,
+            exit: None,
+        };
+
+        let result = run_pre_launch(&hooks, &context);
+        assert!(matches!(
+            result,
+            Err(LaunchError::HookFailed { event: HookEvent::PreLaunch, code: Some(3), .. })
+        ));
+        assert!(!temp.path().join("ran.txt").exists());
+
+        let err = run("exit 4", HookEvent::PostLaunch, &context).unwrap_err();
+        assert_eq!(err.to_string(), "Post-launch hook failed with code 4: exit 4");
+    }
+}
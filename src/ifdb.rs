@@ -1,12 +1,41 @@
 use anyhow::{anyhow, Context, Result};
-use reqwest::Client;
-use serde::Deserialize;
-use std::time::Duration;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use crate::cache::{self, CacheEntry, CacheKey, ResponseCache};
+use crate::settings::Settings;
+
+/// Address of the public IFDB
+pub const DEFAULT_BASE_URL: &str = "https://ifdb.org";
 
 /// IFDB API client for searching and retrieving game information
 pub struct IfdbClient {
     client: Client,
     base_url: String,
+    cache: Option<ResponseCache>,
+    offline: bool,
+}
+
+/// Data returned by [`IfdbClient`], along with where it came from
+#[derive(Debug, Clone)]
+pub struct Fetched<T> {
+    pub data: T,
+    /// True if the data was served from an expired cache entry because
+    /// IFDB could not be reached
+    pub stale: bool,
+}
+
+impl<T> Fetched<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Fetched<U> {
+        Fetched {
+            data: f(self.data),
+            stale: self.stale,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -44,7 +73,7 @@ pub struct SearchResponse {
     pub games: Option<Vec<Game>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct GameDetails {
     pub identification: Option<Identification>,
@@ -101,6 +130,18 @@ impl GameDetails {
         false
     }
 
+    /// Address where the game can be played in a browser, if IFDB lists one
+    pub fn play_online_url(&self) -> Option<&str> {
+        let ifdb = self.ifdb.as_ref()?;
+        ifdb.primary_play_online_url.as_deref().or_else(|| {
+            ifdb.downloads
+                .as_ref()?
+                .links
+                .iter()
+                .find_map(|link| link.play_online_url.as_deref())
+        })
+    }
+
     /// Get the purchase URL from contacts if available
     pub fn get_purchase_url(&self) -> Option<String> {
         match &self.contacts {
@@ -112,7 +153,7 @@ impl GameDetails {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(dead_code)]
 pub enum ContactsField {
@@ -126,7 +167,7 @@ impl Default for ContactsField {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Identification {
     pub ifids: Option<Vec<String>>,
@@ -134,7 +175,7 @@ pub struct Identification {
     pub format: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Bibliographic {
     pub title: Option<String>,
@@ -145,13 +186,13 @@ pub struct Bibliographic {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Contact {
     pub url: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct IfdbData {
     pub tuid: String,
@@ -175,18 +216,18 @@ pub struct IfdbData {
     pub tags: Option<Vec<Tag>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct CoverArt {
     pub url: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Downloads {
     pub links: Vec<DownloadLink>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct DownloadLink {
     pub url: String,
@@ -203,7 +244,7 @@ pub struct DownloadLink {
     pub compressed_primary: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Tag {
     pub name: String,
@@ -216,10 +257,157 @@ pub struct SearchOptions {
     pub query: String,
     pub limit: Option<u32>,
     pub page: Option<u32>,
+    pub filters: SearchFilters,
+}
+
+/// Narrowing of a search, sent to IFDB as `name:value` search operators
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilters {
+    pub genre: Option<String>,
+    /// Development system, e.g. "Inform 7" or "TADS 3"
+    pub system: Option<String>,
+    pub tag: Option<String>,
+    pub language: Option<String>,
+    pub author: Option<String>,
+    /// Lowest average star rating, 1 to 5
+    pub min_rating: Option<u8>,
+    /// First and last year of publication; either end may be open
+    pub year_from: Option<u16>,
+    pub year_to: Option<u16>,
+    pub play_time: Option<PlayTime>,
+    /// Only games with a file to download
+    pub downloadable: bool,
+}
+
+impl SearchFilters {
+    pub fn is_empty(&self) -> bool {
+        *self == SearchFilters::default()
+    }
+
+    /// The filters as IFDB search operators, e.g. `genre:fantasy rating:4-`
+    pub fn operators(&self) -> Vec<String> {
+        let text_filters = [
+            ("genre", &self.genre),
+            ("system", &self.system),
+            ("tag", &self.tag),
+            ("language", &self.language),
+            ("author", &self.author),
+        ];
+        let mut operators: Vec<String> = text_filters
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let value = value.as_deref()?.trim();
+                (!value.is_empty()).then(|| operator(name, value))
+            })
+            .collect();
+
+        if let Some(rating) = self.min_rating {
+            operators.push(format!("rating:{}-", rating));
+        }
+        if self.year_from.is_some() || self.year_to.is_some() {
+            let year = |y: Option<u16>| y.map(|y| y.to_string()).unwrap_or_default();
+            operators.push(format!("published:{}-{}", year(self.year_from), year(self.year_to)));
+        }
+        if let Some(play_time) = self.play_time {
+            operators.push(format!("playtime:{}", play_time.range()));
+        }
+        if self.downloadable {
+            operators.push("downloadable:yes".to_string());
+        }
+        operators
+    }
+}
+
+/// `name:value`, quoting values with spaces so IFDB reads them as one
+fn operator(name: &str, value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("{}:\"{}\"", name, value.replace('"', ""))
+    } else {
+        format!("{}:{}", name, value)
+    }
+}
+
+/// Estimated play time bands, as IFDB offers them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayTime {
+    Under15Minutes,
+    Under30Minutes,
+    UnderAnHour,
+    OneToTwoHours,
+    TwoToFiveHours,
+    OverFiveHours,
+}
+
+impl PlayTime {
+    pub const ALL: [PlayTime; 6] = [
+        PlayTime::Under15Minutes,
+        PlayTime::Under30Minutes,
+        PlayTime::UnderAnHour,
+        PlayTime::OneToTwoHours,
+        PlayTime::TwoToFiveHours,
+        PlayTime::OverFiveHours,
+    ];
+
+    /// Short name used on the command line
+    pub fn id(&self) -> &'static str {
+        match self {
+            PlayTime::Under15Minutes => "15m",
+            PlayTime::Under30Minutes => "30m",
+            PlayTime::UnderAnHour => "1h",
+            PlayTime::OneToTwoHours => "2h",
+            PlayTime::TwoToFiveHours => "5h",
+            PlayTime::OverFiveHours => "long",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayTime::Under15Minutes => "Less than 15 minutes",
+            PlayTime::Under30Minutes => "15-30 minutes",
+            PlayTime::UnderAnHour => "30 minutes - 1 hour",
+            PlayTime::OneToTwoHours => "1-2 hours",
+            PlayTime::TwoToFiveHours => "2-5 hours",
+            PlayTime::OverFiveHours => "More than 5 hours",
+        }
+    }
+
+    /// Value of the `playtime:` operator
+    fn range(&self) -> &'static str {
+        match self {
+            PlayTime::Under15Minutes => "-15m",
+            PlayTime::Under30Minutes => "15m-30m",
+            PlayTime::UnderAnHour => "30m-1h",
+            PlayTime::OneToTwoHours => "1h-2h",
+            PlayTime::TwoToFiveHours => "2h-5h",
+            PlayTime::OverFiveHours => "5h-",
+        }
+    }
+}
+
+impl FromStr for PlayTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PlayTime::ALL
+            .into_iter()
+            .find(|p| p.id().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let ids: Vec<&str> = PlayTime::ALL.iter().map(|p| p.id()).collect();
+                format!("unknown play time '{}' (expected one of: {})", s, ids.join(", "))
+            })
+    }
 }
 
 impl IfdbClient {
     pub fn new() -> Result<Self> {
+        Self::with_base_url(DEFAULT_BASE_URL)
+    }
+
+    /// Client for an IFDB mirror or stand-in at `base_url`
+    pub fn with_base_url(base_url: &str) -> Result<Self> {
+        let base_url = base_url.trim().trim_end_matches('/');
+        Url::parse(base_url).with_context(|| format!("Invalid IFDB address: {}", base_url))?;
+
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .user_agent("glkcli/0.1.0 IF Browser")
@@ -228,16 +416,162 @@ impl IfdbClient {
 
         Ok(IfdbClient {
             client,
-            base_url: "https://ifdb.org".to_string(),
+            base_url: base_url.to_string(),
+            cache: None,
+            offline: false,
         })
     }
 
+    /// Client for the IFDB named by `GLKCLI_IFDB_URL`, the `ifdb_url`
+    /// setting or the public site, in that order
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let base_url = env::var("GLKCLI_IFDB_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .or_else(|| settings.ifdb_url.clone())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        Self::with_base_url(&base_url)
+    }
+
+    /// Address of the IFDB this client talks to, without a trailing `/`
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Serve and store responses through an on-disk cache
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// When offline, requests are answered from the cache only
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// Fetch a response body, going through the cache if one is configured
+    ///
+    /// Fresh cache entries are returned without touching the network. Expired
+    /// entries are revalidated with `If-None-Match` / `If-Modified-Since`, and
+    /// are served as stale data if IFDB cannot be reached or returns an error.
+    async fn fetch_text(&self, url: &str, key: CacheKey, ttl: Duration, what: &str) -> Result<Fetched<String>> {
+        let cached = self.cache.as_ref().and_then(|c| c.get(&key));
+
+        if let Some(entry) = &cached {
+            if self.offline || entry.is_fresh(ttl) {
+                return Ok(Fetched {
+                    data: entry.body.clone(),
+                    stale: !entry.is_fresh(ttl),
+                });
+            }
+        } else if self.offline {
+            return Err(anyhow!("No cached {} data available offline", what));
+        }
+
+        let mut request = self.client.get(url);
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let result = async {
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Failed to send {} request", what))?;
+
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED {
+                return Ok(None);
+            }
+            if !status.is_success() {
+                return Err(anyhow!("IFDB {} request failed: {}", what, status));
+            }
+
+            let header = |name| {
+                response.headers()
+                    .get(name)
+                    .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                    .map(|v| v.to_string())
+            };
+            let etag = header(ETAG);
+            let last_modified = header(LAST_MODIFIED);
+
+            let body = response
+                .text()
+                .await
+                .with_context(|| format!("Failed to read {} response", what))?;
+
+            Ok(Some(CacheEntry {
+                url: url.to_string(),
+                fetched_at: SystemTime::now(),
+                etag,
+                last_modified,
+                body,
+            }))
+        }.await;
+
+        let entry = match (result, cached) {
+            (Ok(Some(fresh)), _) => fresh,
+            (Ok(None), Some(mut revalidated)) => {
+                revalidated.fetched_at = SystemTime::now();
+                revalidated
+            }
+            (Ok(None), None) => {
+                return Err(anyhow!("IFDB {} request returned 304 without a cached copy", what));
+            }
+            (Err(e), Some(entry)) => {
+                log::warn!("Serving cached {} data after request failure: {}", what, e);
+                return Ok(Fetched {
+                    data: entry.body,
+                    stale: true,
+                });
+            }
+            (Err(e), None) => return Err(e),
+        };
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(&key, &entry) {
+                log::warn!("Failed to cache {} response: {}", what, e);
+            }
+        }
+
+        Ok(Fetched {
+            data: entry.body,
+            stale: false,
+        })
+    }
+
+    /// Address of an IFDB page with its query parameters percent-encoded
+    ///
+    /// Every request is built here, so values are encoded as UTF-8 the same
+    /// way everywhere. Parameters with an empty value are sent as bare flags
+    /// (`?json`), as IFDB's own links do.
+    fn endpoint(&self, path: &str, params: &[(&str, String)]) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/{}", self.base_url, path))
+            .with_context(|| format!("Invalid IFDB address: {}", self.base_url))?;
+        if !params.is_empty() {
+            let mut query = url.query_pairs_mut();
+            for (name, value) in params {
+                if value.is_empty() {
+                    query.append_key_only(name);
+                } else {
+                    query.append_pair(name, value);
+                }
+            }
+        }
+        Ok(url)
+    }
+
     /// Search for games on IFDB
-    pub async fn search_games(&self, options: &SearchOptions) -> Result<Vec<Game>> {
-        let mut url = format!("{}/search", self.base_url);
+    pub async fn search_games(&self, options: &SearchOptions) -> Result<Fetched<Vec<Game>>> {
         let mut params = vec![
-            ("json", "".to_string()),
-            ("searchfor", options.query.clone()),
+            ("json", String::new()),
+            ("searchfor", options.search_text()),
             ("searchgo", "Search Games".to_string()),
         ];
 
@@ -250,96 +584,48 @@ impl IfdbClient {
             params.push(("pg", page.to_string()));
         }
 
-        let query_string = params
-            .iter()
-            .map(|(k, v)| {
-                if v.is_empty() {
-                    k.to_string()
-                } else {
-                    format!("{}={}", k, urlencoding::encode(v))
-                }
-            })
-            .collect::<Vec<_>>()
-            .join("&");
+        let url = self.endpoint("search", &params)?;
 
-        url = format!("{}?{}", url, query_string);
+        let fetched = self
+            .fetch_text(url.as_str(), CacheKey::Listing(url.to_string()), cache::LISTING_TTL, "search")
+            .await?;
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send search request")?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("Search request failed: {}", response.status()));
-        }
-
-        let search_response: SearchResponse = response
-            .json()
-            .await
+        let search_response: SearchResponse = serde_json::from_str(&fetched.data)
             .context("Failed to parse search response")?;
 
+        // Every game is returned; which of them glkcli can play is decided
+        // by the caller, against the interpreters that are installed
         let games = search_response.games.unwrap_or_default();
-        
-        // Filter by devsys - only include GLK-compatible development systems
-        // These are the systems that produce games we can run with GLK interpreters
-        let filtered_games: Vec<Game> = games
-            .into_iter()
-            .filter(|game| {
-                // If no devsys specified, include it (might be in multiple formats)
-                let Some(devsys) = &game.devsys else {
-                    return true;
-                };
-                
-                let devsys_lower = devsys.to_lowercase();
-                
-                // Whitelist of GLK-compatible development systems
-                let glk_systems = [
-                    "inform", "tads", "adrift", "hugo", "alan", "quest",
-                    "dialog", "zil", "axma", "choicescript", "dendry", 
-                    "windrift"
-                ];
-                
-                // Check if devsys contains any of our whitelisted systems
-                glk_systems.iter().any(|sys| devsys_lower.contains(sys))
-            })
-            .collect();
-
-        Ok(filtered_games)
+        Ok(fetched.map(|_| games))
     }
 
     /// Get detailed information about a specific game by TUID
-    pub async fn get_game_details(&self, tuid: &str) -> Result<GameDetails> {
-        let url = format!("{}/viewgame?json&id={}", self.base_url, tuid);
+    pub async fn get_game_details(&self, tuid: &str) -> Result<Fetched<GameDetails>> {
+        let url = self.endpoint("viewgame", &[("json", String::new()), ("id", tuid.to_string())])?;
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send game details request")?;
+        let fetched = self
+            .fetch_text(url.as_str(), CacheKey::Details(tuid.to_string()), cache::DETAILS_TTL, "game details")
+            .await?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Game details request failed: {}", response.status()));
-        }
-
-        let game_details: GameDetails = response
-            .json()
-            .await
+        let game_details: GameDetails = serde_json::from_str(&fetched.data)
             .context("Failed to parse game details response")?;
 
-        Ok(game_details)
+        Ok(fetched.map(|_| game_details))
+    }
+
+    /// Game details already in the cache, however old, without any request
+    pub fn cached_game_details(&self, tuid: &str) -> Option<GameDetails> {
+        let entry = self.cache.as_ref()?.get(&CacheKey::Details(tuid.to_string()))?;
+        serde_json::from_str(&entry.body).ok()
     }
 
     /// Browse popular/recent games (using empty search with sorting)
-    pub async fn browse_games(&self, sort_by: Option<&str>) -> Result<Vec<Game>> {
+    pub async fn browse_games(&self, sort_by: Option<&str>) -> Result<Fetched<Vec<Game>>> {
         // For browsing, we need to use the browse parameter instead of searchfor
         // to get proper rating-based sorting
-        let mut url = format!("{}/search", self.base_url);
         let mut params = vec![
-            ("browse", "".to_string()),
-            ("json", "".to_string()),
+            ("browse", String::new()),
+            ("json", String::new()),
         ];
         
         // Add sort parameter if specified
@@ -354,62 +640,39 @@ impl IfdbClient {
         
         params.push(("count", "50".to_string()));
 
-        let query_string = params
-            .iter()
-            .map(|(k, v)| {
-                if v.is_empty() {
-                    k.to_string()
-                } else {
-                    format!("{}={}", k, urlencoding::encode(v))
-                }
-            })
-            .collect::<Vec<_>>()
-            .join("&");
+        let url = self.endpoint("search", &params)?;
 
-        url = format!("{}?{}", url, query_string);
+        let fetched = self
+            .fetch_text(url.as_str(), CacheKey::Listing(url.to_string()), cache::LISTING_TTL, "browse")
+            .await?;
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send browse request")?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("Browse request failed: {}", response.status()));
-        }
-
-        let search_response: SearchResponse = response
-            .json()
-            .await
+        let search_response: SearchResponse = serde_json::from_str(&fetched.data)
             .context("Failed to parse browse response")?;
 
+        // Every game is returned; which of them glkcli can play is decided
+        // by the caller, against the interpreters that are installed
         let games = search_response.games.unwrap_or_default();
-        
-        // Filter by devsys - only include GLK-compatible development systems
-        let filtered_games: Vec<Game> = games
-            .into_iter()
-            .filter(|game| {
-                // If no devsys specified, include it (might be in multiple formats)
-                let Some(devsys) = &game.devsys else {
-                    return true;
-                };
-                
-                let devsys_lower = devsys.to_lowercase();
-                
-                // Whitelist of GLK-compatible development systems
-                let glk_systems = [
-                    "inform", "tads", "adrift", "hugo", "alan", "quest",
-                    "dialog", "zil", "axma", "choicescript", "dendry", 
-                    "windrift"
-                ];
-                
-                // Check if devsys contains any of our whitelisted systems
-                glk_systems.iter().any(|sys| devsys_lower.contains(sys))
-            })
-            .collect();
+        Ok(fetched.map(|_| games))
+    }
 
-        Ok(filtered_games)
+    /// Get the cover image for a game, downloading it into the cache if needed
+    pub async fn fetch_cover_art(&self, tuid: &str, url: &str) -> Result<std::path::PathBuf> {
+        let cache = self.cache
+            .as_ref()
+            .ok_or_else(|| anyhow!("No cache available for cover art"))?;
+
+        let path = cache.cover_path(tuid);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        if self.offline {
+            return Err(anyhow!("Cover art not cached and network unavailable"));
+        }
+
+        let response = self.download_file(url).await?;
+        let bytes = response.bytes().await.context("Failed to read cover art")?;
+        cache.put_cover(tuid, &bytes)
     }
 
     /// Download a file from a URL
@@ -441,9 +704,17 @@ impl SearchOptions {
             query: query.into(),
             limit: None,
             page: None,
+            filters: SearchFilters::default(),
         }
     }
 
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        let downloadable = self.filters.downloadable;
+        self.filters = filters;
+        self.filters.downloadable |= downloadable;
+        self
+    }
+
     pub fn with_limit(mut self, limit: u32) -> Self {
         self.limit = Some(limit);
         self
@@ -457,51 +728,298 @@ impl SearchOptions {
 
     pub fn with_glk_formats(mut self) -> Self {
         // Simple approach: just search for downloadable games
-        // We'll filter by devsys on the client side
-        let filters = "downloadable:yes";
-        
-        if self.query.is_empty() {
-            self.query = filters.to_string();
-        } else {
-            self.query = format!("{} {}", self.query, filters);
-        }
+        // Which of the results glkcli can play is decided in the browser
+        self.filters.downloadable = true;
         self
     }
-}
 
-// Helper function for URL encoding (simple implementation)
-mod urlencoding {
-    pub fn encode(input: &str) -> String {
-        input
-            .chars()
-            .map(|c| match c {
-                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-                ' ' => "+".to_string(),
-                _ => format!("%{:02X}", c as u8),
-            })
-            .collect()
+    /// The query with the filters' operators after it, as sent to IFDB
+    pub fn search_text(&self) -> String {
+        std::iter::once(self.query.trim().to_string())
+            .chain(self.filters.operators())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mockito::Matcher;
+    use serial_test::serial;
+    use tempfile::TempDir;
 
-    #[tokio::test]
-    async fn test_search_games() {
+    #[test]
+    fn test_search_text() {
+        assert_eq!(SearchOptions::new("zork").search_text(), "zork");
+        assert_eq!(SearchOptions::new("").with_glk_formats().search_text(), "downloadable:yes");
+
+        let filters = SearchFilters {
+            genre: Some("science fiction".to_string()),
+            system: Some("Inform 7".to_string()),
+            language: Some("en".to_string()),
+            min_rating: Some(4),
+            year_from: Some(2010),
+            play_time: Some(PlayTime::UnderAnHour),
+            ..SearchFilters::default()
+        };
+        let options = SearchOptions::new(" robots ").with_glk_formats().with_filters(filters);
+        assert_eq!(
+            options.search_text(),
+            "robots genre:\"science fiction\" system:\"Inform 7\" language:en \
+             rating:4- published:2010- playtime:30m-1h downloadable:yes"
+        );
+
+        let filters = SearchFilters { year_to: Some(1990), ..SearchFilters::default() };
+        assert_eq!(filters.operators(), vec!["published:-1990"]);
+        assert!(SearchFilters::default().is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_encodes_unicode_and_reserved_characters() {
         let client = IfdbClient::new().unwrap();
-        let options = SearchOptions::new("Zork").with_limit(5);
-        
-        // Note: This test requires network access and will fail if IFDB is down
-        let result = client.search_games(&options).await;
-        match result {
-            Ok(games) => {
-                println!("Found {} games", games.len());
-                assert!(!games.is_empty());
-            }
-            Err(e) => {
-                println!("Search failed (this is expected in CI): {}", e);
-            }
+
+        let url = client
+            .endpoint("search", &[("json", String::new()), ("searchfor", "Café ゼルダ".to_string())])
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://ifdb.org/search?json&searchfor=Caf%C3%A9+%E3%82%BC%E3%83%AB%E3%83%80"
+        );
+
+        let url = client
+            .endpoint("search", &[("searchfor", "a&b=c+d #1? 50%/x".to_string())])
+            .unwrap();
+        assert_eq!(url.query(), Some("searchfor=a%26b%3Dc%2Bd+%231%3F+50%25%2Fx"));
+
+        // Decoding gives back exactly what was sent
+        let (_, value) = url.query_pairs().next().unwrap();
+        assert_eq!(value, "a&b=c+d #1? 50%/x");
+
+        let url = client.endpoint("viewgame", &[("id", "0dbnusxunq7fw5ro".to_string())]).unwrap();
+        assert_eq!(url.as_str(), "https://ifdb.org/viewgame?id=0dbnusxunq7fw5ro");
+    }
+
+    #[test]
+    fn test_play_time_ids() {
+        for play_time in PlayTime::ALL {
+            assert_eq!(play_time.id().parse::<PlayTime>(), Ok(play_time));
         }
+        assert!("forever".parse::<PlayTime>().is_err());
+    }
+
+    fn fixture(name: &str) -> String {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ifdb").join(name);
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+    }
+
+    fn json_response(server: &mut mockito::ServerGuard, path: &str, body: String) -> mockito::Mock {
+        server
+            .mock("GET", path)
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+    }
+
+    /// A cache holding `body` for `key`, fetched long enough ago to have expired
+    fn expired_cache(temp: &TempDir, key: &CacheKey, body: String, etag: Option<&str>) -> ResponseCache {
+        let cache = ResponseCache::with_dir(temp.path().to_path_buf()).unwrap();
+        let entry = CacheEntry {
+            url: String::new(),
+            fetched_at: SystemTime::UNIX_EPOCH,
+            etag: etag.map(str::to_string),
+            last_modified: None,
+            body,
+        };
+        cache.put(key, &entry).unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_search_games() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = json_response(&mut server, "/search", fixture("search.json"))
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("json".into(), String::new()),
+                Matcher::UrlEncoded("searchfor".into(), "Café ゼルダ downloadable:yes".into()),
+                Matcher::UrlEncoded("count".into(), "5".into()),
+                Matcher::UrlEncoded("pg".into(), "2".into()),
+            ]))
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let options = SearchOptions::new("Café ゼルダ").with_limit(5).with_page(2).with_glk_formats();
+        let games = client.search_games(&options).await.unwrap();
+        mock.assert_async().await;
+
+        assert!(!games.stale);
+        assert_eq!(games.data.len(), 3);
+        let zork = &games.data[0];
+        assert_eq!(zork.tuid, "0dbnusxunq7fw5ro");
+        assert_eq!(zork.devsys.as_deref(), Some("ZIL"));
+        assert_eq!(zork.star_rating, Some(4.0));
+        assert_eq!(zork.published.as_ref().map(|p| p.machine.as_str()), Some("1980-12-01"));
+        assert_eq!(games.data[1].title, "Café Zork");
+        assert!(games.data[1].published.is_none());
+        assert_eq!(games.data[2].author, "山田太郎");
+        assert!(games.data[2].devsys.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_browse_games() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = json_response(&mut server, "/search", fixture("browse.json"))
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("browse".into(), String::new()),
+                Matcher::UrlEncoded("sortby".into(), "ratu".into()),
+                Matcher::UrlEncoded("count".into(), "50".into()),
+            ]))
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let games = client.browse_games(Some("rating")).await.unwrap();
+        mock.assert_async().await;
+
+        let titles: Vec<&str> = games.data.iter().map(|g| g.title.as_str()).collect();
+        assert_eq!(titles, vec!["Counterfeit Monkey", "Photopia"]);
+        assert_eq!(games.data[0].play_time_in_minutes, Some(480));
+    }
+
+    #[tokio::test]
+    async fn test_game_details_with_contact_list() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = json_response(&mut server, "/viewgame", fixture("details_free.json"))
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("json".into(), String::new()),
+                Matcher::UrlEncoded("id".into(), "0dbnusxunq7fw5ro".into()),
+            ]))
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let details = client.get_game_details("0dbnusxunq7fw5ro").await.unwrap().data;
+        mock.assert_async().await;
+
+        assert!(matches!(details.contacts, ContactsField::Array(ref contacts) if contacts.len() == 2));
+        assert_eq!(details.get_purchase_url().as_deref(), Some("https://www.infocom-if.org/"));
+        // Tagged commercial, but it has a story file to download
+        assert!(!details.is_commercial());
+        assert_eq!(
+            details.play_online_url(),
+            Some("https://iplayif.com/?story=https://example.org/zork1.z5")
+        );
+        assert_eq!(details.bibliographic.and_then(|b| b.genre).as_deref(), Some("Zorkian"));
+    }
+
+    #[tokio::test]
+    async fn test_game_details_with_single_contact() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = json_response(&mut server, "/viewgame", fixture("details_commercial.json"))
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let details = client.get_game_details("c0mmerc1alg4me00").await.unwrap().data;
+
+        assert!(matches!(details.contacts, ContactsField::Object(_)));
+        assert_eq!(
+            details.get_purchase_url().as_deref(),
+            Some("https://store.example.com/paid-adventure")
+        );
+        assert!(details.is_commercial());
+        assert!(details.play_online_url().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let _search = server.mock("GET", "/search").match_query(Matcher::Any).with_status(500).create_async().await;
+        let _details = server.mock("GET", "/viewgame").match_query(Matcher::Any).with_status(404).create_async().await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let error = client.search_games(&SearchOptions::new("zork")).await.unwrap_err();
+        assert!(error.to_string().contains("500"), "{}", error);
+        let error = client.get_game_details("missing").await.unwrap_err();
+        assert!(error.to_string().contains("404"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_payloads() {
+        let mut server = mockito::Server::new_async().await;
+        let _search = json_response(&mut server, "/search", fixture("malformed.json")).create_async().await;
+        let _details = json_response(&mut server, "/viewgame", "<html>Maintenance</html>".to_string())
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap();
+        let error = client.search_games(&SearchOptions::new("zork")).await.unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse search response");
+        let error = client.get_game_details("0dbnusxunq7fw5ro").await.unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse game details response");
+    }
+
+    #[tokio::test]
+    async fn test_expired_cache_served_when_ifdb_fails() {
+        let temp = TempDir::new().unwrap();
+        let key = CacheKey::Details("0dbnusxunq7fw5ro".to_string());
+        let cache = expired_cache(&temp, &key, fixture("details_free.json"), None);
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/viewgame").match_query(Matcher::Any).with_status(503).create_async().await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap().with_cache(cache);
+        let details = client.get_game_details("0dbnusxunq7fw5ro").await.unwrap();
+        assert!(details.stale);
+        assert_eq!(details.data.ifdb.unwrap().tuid, "0dbnusxunq7fw5ro");
+    }
+
+    #[tokio::test]
+    async fn test_expired_cache_revalidated() {
+        let temp = TempDir::new().unwrap();
+        let key = CacheKey::Details("0dbnusxunq7fw5ro".to_string());
+        let cache = expired_cache(&temp, &key, fixture("details_free.json"), Some("\"v1\""));
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/viewgame")
+            .match_query(Matcher::Any)
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create_async()
+            .await;
+
+        let client = IfdbClient::with_base_url(&server.url()).unwrap().with_cache(cache);
+        let details = client.get_game_details("0dbnusxunq7fw5ro").await.unwrap();
+        mock.assert_async().await;
+        assert!(!details.stale);
+        assert!(client.cache.as_ref().unwrap().get(&key).unwrap().is_fresh(cache::DETAILS_TTL));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_offline_without_cache() {
+        let mut client = IfdbClient::with_base_url("http://127.0.0.1:9").unwrap();
+        client.set_offline(true);
+        let error = client.search_games(&SearchOptions::new("zork")).await.unwrap_err();
+        assert_eq!(error.to_string(), "No cached search data available offline");
+    }
+
+    #[test]
+    #[serial]
+    fn test_base_url_from_settings() {
+        let mut settings = Settings::default();
+        env::remove_var("GLKCLI_IFDB_URL");
+        assert_eq!(IfdbClient::from_settings(&settings).unwrap().base_url, DEFAULT_BASE_URL);
+
+        settings.ifdb_url = Some("http://mirror.example.org/ifdb/".to_string());
+        assert_eq!(IfdbClient::from_settings(&settings).unwrap().base_url, "http://mirror.example.org/ifdb");
+
+        env::set_var("GLKCLI_IFDB_URL", "http://localhost:8080");
+        assert_eq!(IfdbClient::from_settings(&settings).unwrap().base_url, "http://localhost:8080");
+        env::remove_var("GLKCLI_IFDB_URL");
+
+        assert!(IfdbClient::with_base_url("not a url").is_err());
+    }
+}
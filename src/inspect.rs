@@ -0,0 +1,429 @@
+//! Reading the metadata stored in story file headers
+//!
+//! Detection only decides what a file is. Most formats also record which
+//! build of a game the file holds - a Z-code release and serial number, the
+//! compiler that produced it, a checksum - and this module reports those
+//! fields. Stories inside a Blorb are read from the executable chunk.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::config::GameFormat;
+use crate::detect::{self, story_extent, StoryExtent};
+use crate::error::DetectError;
+use crate::utils::format_size;
+
+/// How much of a TADS 3 image to search for its GameInfo resource
+const TADS3_SCAN_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Bytes of header read for every other format
+const HEADER_LEN: u64 = 64;
+
+/// One named value from a story header
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Field {
+    pub name: &'static str,
+    pub value: String,
+}
+
+/// What a story file's header says about it
+#[derive(Debug, Clone, Serialize)]
+pub struct StoryInfo {
+    pub format: &'static str,
+    /// Header fields in the order they are shown
+    pub fields: Vec<Field>,
+    /// Short description of the build, e.g. "Release 88 / 840726"
+    pub release: Option<String>,
+}
+
+impl StoryInfo {
+    fn new(format: GameFormat) -> Self {
+        StoryInfo {
+            format: format.name(),
+            fields: Vec::new(),
+            release: None,
+        }
+    }
+
+    fn push(&mut self, name: &'static str, value: impl Into<String>) {
+        self.fields.push(Field { name, value: value.into() });
+    }
+}
+
+/// Detect a story file's format and read the metadata in its header
+///
+/// Formats without a known header layout are reported with just their
+/// format and size.
+pub fn inspect(path: &Path) -> Result<StoryInfo> {
+    if !path.exists() {
+        return Err(DetectError::NotFound(path.to_path_buf()).into());
+    }
+
+    let format = detect::detect(path)?.format;
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open story file: {}", path.display()))?;
+    let StoryExtent { start, len, in_blorb } = story_extent(&mut file).context("Failed to read story file")?;
+
+    let mut info = StoryInfo::new(format);
+    if in_blorb {
+        info.push("Container", "Blorb");
+    }
+
+    match format {
+        GameFormat::ZCode => zcode_fields(&mut info, &read_at(&mut file, start, HEADER_LEN)?, len),
+        GameFormat::Glulx => glulx_fields(&mut info, &read_at(&mut file, start, HEADER_LEN)?),
+        GameFormat::Tads => {
+            tads_fields(&mut info, &read_at(&mut file, start, len.min(TADS3_SCAN_LIMIT))?)
+        }
+        GameFormat::Hugo => hugo_fields(&mut info, &read_at(&mut file, start, HEADER_LEN)?),
+        _ => {}
+    }
+
+    info.push("Size", format_size(len));
+    Ok(info)
+}
+
+/// Read up to `len` bytes starting at `start`
+pub(crate) fn read_at(file: &mut File, start: u64, len: u64) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.take(len).read_to_end(&mut data))
+        .context("Failed to read story file")?;
+    Ok(data)
+}
+
+pub(crate) fn be16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+pub(crate) fn be32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Printable ASCII text in `data[range]`, without NUL padding or whitespace
+fn text(data: &[u8], range: std::ops::Range<usize>) -> Option<String> {
+    let bytes = data.get(range)?;
+    let bytes = &bytes[..bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len())];
+    let text = std::str::from_utf8(bytes).ok()?.trim();
+    (!text.is_empty() && text.chars().all(|c| c.is_ascii_graphic() || c == ' ')).then(|| text.to_string())
+}
+
+/// Serial numbers are conventionally the compile date as YYMMDD
+fn release_summary(release: u16, serial: Option<&str>) -> String {
+    match serial {
+        Some(serial) => format!("Release {} / {}", release, serial),
+        None => format!("Release {}", release),
+    }
+}
+
+/// Z-code: the 64-byte header described in the Z-Machine Standard, section 11
+fn zcode_fields(info: &mut StoryInfo, header: &[u8], len: u64) {
+    let Some(&version) = header.first() else {
+        return;
+    };
+    info.push("Version", version.to_string());
+
+    let Some(release) = be16(header, 0x02) else {
+        return;
+    };
+    let serial = text(header, 0x12..0x18);
+    info.push("Release", release.to_string());
+    if let Some(serial) = &serial {
+        info.push("Serial", serial.clone());
+    }
+    info.release = Some(release_summary(release, serial.as_deref()));
+
+    // Versions 1 and 2 have neither a length nor a checksum
+    if version >= 3 {
+        if let Some(checksum) = be16(header, 0x1C) {
+            info.push("Checksum", format!("0x{:04x}", checksum));
+        }
+    }
+
+    // Inform writes its version here; Infocom's compiler left it blank
+    if let Some(compiler) = text(header, 0x3C..0x40) {
+        match compiler.as_bytes().get(1) {
+            Some(b'.') => info.push("Compiler", format!("Inform {}", compiler)),
+            _ => info.push("Compiler", compiler),
+        }
+    }
+
+    let multiplier = match version {
+        1..=3 => 2,
+        4 | 5 => 4,
+        _ => 8,
+    };
+    let stated = match be16(header, 0x1A) {
+        Some(words) if version >= 3 && words > 0 => words as u64 * multiplier,
+        _ => len,
+    };
+    info.push("Length", format!("{} bytes", stated));
+}
+
+/// Glulx: the 36-byte header, followed in Inform games by an "Info" block
+fn glulx_fields(info: &mut StoryInfo, header: &[u8]) {
+    let (Some(version), Some(ram_start), Some(ext_start), Some(end_mem), Some(stack), Some(checksum)) = (
+        be32(header, 4),
+        be32(header, 8),
+        be32(header, 12),
+        be32(header, 16),
+        be32(header, 20),
+        be32(header, 32),
+    ) else {
+        return;
+    };
+
+    info.push(
+        "Glulx version",
+        format!("{}.{}.{}", version >> 16, (version >> 8) & 0xff, version & 0xff),
+    );
+    info.push("RAM", format!("0x{:x}-0x{:x}", ram_start, end_mem));
+    info.push("Extended memory", format_size(end_mem.saturating_sub(ext_start) as u64));
+    info.push("Stack", format_size(stack as u64));
+    info.push("Checksum", format!("0x{:08x}", checksum));
+
+    if header.get(36..40) != Some(b"Info") {
+        return;
+    }
+    if let Some(compiler) = text(header, 44..48) {
+        info.push("Compiler", format!("Inform {}", compiler));
+    }
+    if let Some(release) = be16(header, 52) {
+        let serial = text(header, 54..60);
+        info.push("Release", release.to_string());
+        if let Some(serial) = &serial {
+            info.push("Serial", serial.clone());
+        }
+        info.release = Some(release_summary(release, serial.as_deref()));
+    }
+}
+
+/// TADS 2 and 3: compiler version and build time, plus a TADS 3 game's GameInfo
+fn tads_fields(info: &mut StoryInfo, data: &[u8]) {
+    if data.starts_with(b"TADS2 bin") {
+        if let Some(version) = text(data, 13..19) {
+            info.push("TADS version", version.trim_start_matches('v').to_string());
+        }
+        if let Some(compiled) = text(data, 21..45) {
+            info.push("Compiled", compiled);
+        }
+        return;
+    }
+
+    if data.starts_with(b"T3-image") {
+        info.push("TADS version", "3");
+        if let Some(version) = data.get(11..13) {
+            info.push("Image version", u16::from_le_bytes([version[0], version[1]]).to_string());
+        }
+        if let Some(compiled) = text(data, 45..69) {
+            info.push("Compiled", compiled);
+        }
+    }
+
+    let game_info = tads_game_info(data);
+    let value = |key: &str| game_info.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    for (key, name) in [
+        ("Name", "Title"),
+        ("Byline", "Byline"),
+        ("Version", "Game version"),
+        ("ReleaseDate", "Release date"),
+        ("IFID", "IFID"),
+    ] {
+        if let Some(v) = value(key) {
+            info.push(name, v);
+        }
+    }
+
+    info.release = match (value("Version"), value("ReleaseDate")) {
+        (Some(version), Some(date)) => Some(format!("Version {} / {}", version, date)),
+        (Some(version), None) => Some(format!("Version {}", version)),
+        (None, Some(date)) => Some(date),
+        (None, None) => None,
+    };
+}
+
+/// The `Key: value` lines of a TADS 3 GameInfo resource, if the image has one
+///
+/// The resource is plain text stored in the image, so it is found by
+/// searching for its `Name:` line rather than by walking the resource table.
+fn tads_game_info(data: &[u8]) -> Vec<(String, String)> {
+    const MARKER: &[u8] = b"Name:";
+
+    let mut from = 0;
+    while let Some(found) = data[from..].windows(MARKER.len()).position(|w| w == MARKER) {
+        let at = from + found;
+        from = at + 1;
+        if at > 0 && data[at - 1].is_ascii_alphanumeric() {
+            continue;
+        }
+
+        let end = data[at..]
+            .iter()
+            .position(|&b| b == 0 || (b < 0x20 && !b"\r\n\t".contains(&b)))
+            .map_or(data.len(), |n| at + n);
+        let block = String::from_utf8_lossy(&data[at..end]);
+
+        let mut pairs = Vec::new();
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                break;
+            };
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+                break;
+            }
+            pairs.push((key.to_string(), value.trim().to_string()));
+        }
+
+        // A lone "Name:" is more likely game text than the resource
+        if pairs.len() >= 2 {
+            return pairs;
+        }
+    }
+
+    Vec::new()
+}
+
+/// Hugo: the compiler version and the "MM-DD-YY" compile date
+fn hugo_fields(info: &mut StoryInfo, header: &[u8]) {
+    let Some(&version) = header.first() else {
+        return;
+    };
+    info.push("Hugo version", format!("{}.{}", version / 10, version % 10));
+    if let Some(compiled) = text(header, 3..11) {
+        info.push("Compiled", compiled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::test_story;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn field<'a>(info: &'a StoryInfo, name: &str) -> Option<&'a str> {
+        info.fields.iter().find(|f| f.name == name).map(|f| f.value.as_str())
+    }
+
+    fn inspect_bytes(data: &[u8]) -> StoryInfo {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(data).unwrap();
+        inspect(file.path()).unwrap()
+    }
+
+    #[test]
+    fn test_inspect_zcode() {
+        let mut story = test_story(3);
+        story[0x02..0x04].copy_from_slice(&88u16.to_be_bytes());
+        story[0x12..0x18].copy_from_slice(b"840726");
+        story[0x3C..0x40].copy_from_slice(b"6.21");
+        let info = inspect_bytes(&story);
+
+        assert_eq!(info.format, "Z-code");
+        assert_eq!(field(&info, "Version"), Some("3"));
+        assert_eq!(field(&info, "Serial"), Some("840726"));
+        assert_eq!(field(&info, "Compiler"), Some("Inform 6.21"));
+        assert_eq!(field(&info, "Length"), Some("1024 bytes"));
+        assert!(field(&info, "Checksum").unwrap().starts_with("0x"));
+        assert_eq!(info.release.as_deref(), Some("Release 88 / 840726"));
+    }
+
+    #[test]
+    fn test_inspect_zcode_in_blorb() {
+        let story = test_story(5);
+        let mut data = vec![0u8; 48];
+        data[0..4].copy_from_slice(b"FORM");
+        data[8..12].copy_from_slice(b"IFRS");
+        data[12..16].copy_from_slice(b"RIdx");
+        data[16..20].copy_from_slice(&16u32.to_be_bytes());
+        data[20..24].copy_from_slice(&1u32.to_be_bytes());
+        data[24..28].copy_from_slice(b"Exec");
+        data[32..36].copy_from_slice(&36u32.to_be_bytes());
+        data.truncate(36);
+        data.extend_from_slice(b"ZCOD");
+        data.extend_from_slice(&(story.len() as u32).to_be_bytes());
+        data.extend_from_slice(&story);
+        data.extend_from_slice(&[0u8; 100]);
+
+        let info = inspect_bytes(&data);
+        assert_eq!(field(&info, "Container"), Some("Blorb"));
+        assert_eq!(field(&info, "Version"), Some("5"));
+        assert_eq!(field(&info, "Size"), Some(format_size(1024).as_str()));
+    }
+
+    #[test]
+    fn test_inspect_glulx() {
+        let mut data = vec![0u8; 256];
+        data[0..4].copy_from_slice(b"Glul");
+        data[4..8].copy_from_slice(&0x0003_0102u32.to_be_bytes());
+        data[8..12].copy_from_slice(&0x100u32.to_be_bytes());
+        data[12..16].copy_from_slice(&0x200u32.to_be_bytes());
+        data[16..20].copy_from_slice(&0x600u32.to_be_bytes());
+        data[20..24].copy_from_slice(&0x400u32.to_be_bytes());
+        data[32..36].copy_from_slice(&0xdeadbeefu32.to_be_bytes());
+        data[36..40].copy_from_slice(b"Info");
+        data[44..48].copy_from_slice(b"6.33");
+        data[52..54].copy_from_slice(&2u16.to_be_bytes());
+        data[54..60].copy_from_slice(b"150101");
+        let info = inspect_bytes(&data);
+
+        assert_eq!(field(&info, "Glulx version"), Some("3.1.2"));
+        assert_eq!(field(&info, "RAM"), Some("0x100-0x600"));
+        assert_eq!(field(&info, "Extended memory"), Some(format_size(0x400).as_str()));
+        assert_eq!(field(&info, "Checksum"), Some("0xdeadbeef"));
+        assert_eq!(field(&info, "Compiler"), Some("Inform 6.33"));
+        assert_eq!(info.release.as_deref(), Some("Release 2 / 150101"));
+    }
+
+    #[test]
+    fn test_inspect_tads2() {
+        let mut data = vec![0u8; 64];
+        data[0..12].copy_from_slice(b"TADS2 bin\x0A\x0D\x1A");
+        data[13..19].copy_from_slice(b"v2.5.9");
+        data[21..45].copy_from_slice(b"Sat Jun 01 12:00:00 2002");
+        let info = inspect_bytes(&data);
+
+        assert_eq!(field(&info, "TADS version"), Some("2.5.9"));
+        assert_eq!(field(&info, "Compiled"), Some("Sat Jun 01 12:00:00 2002"));
+        assert_eq!(info.release, None);
+    }
+
+    #[test]
+    fn test_inspect_tads3_game_info() {
+        let mut data = vec![0u8; 128];
+        data[0..11].copy_from_slice(b"T3-image\x0D\x0A\x1A");
+        data[11..13].copy_from_slice(&1u16.to_le_bytes());
+        data[45..69].copy_from_slice(b"Sun Mar 02 10:15:00 2008");
+        // Game text mentioning the key must not be taken for the resource
+        data.extend_from_slice(b"\0Name: nobody\0");
+        data.extend_from_slice(b"Name: Blighted Isle\r\nByline: by Eric Eve\r\nVersion: 2\r\nReleaseDate: 2008-03-02\r\n\0");
+        let info = inspect_bytes(&data);
+
+        assert_eq!(info.format, "TADS");
+        assert_eq!(field(&info, "Image version"), Some("1"));
+        assert_eq!(field(&info, "Title"), Some("Blighted Isle"));
+        assert_eq!(field(&info, "Byline"), Some("by Eric Eve"));
+        assert_eq!(info.release.as_deref(), Some("Version 2 / 2008-03-02"));
+    }
+
+    #[test]
+    fn test_inspect_hugo() {
+        let mut data = vec![0u8; 0x100];
+        data[0] = 31;
+        data[3..11].copy_from_slice(b"04-01-05");
+        let info = inspect_bytes(&data);
+
+        assert_eq!(info.format, "Hugo");
+        assert_eq!(field(&info, "Hugo version"), Some("3.1"));
+        assert_eq!(field(&info, "Compiled"), Some("04-01-05"));
+    }
+
+    #[test]
+    fn test_inspect_missing_file() {
+        let err = inspect(Path::new("/nonexistent/zork.z5")).unwrap_err();
+        assert!(matches!(err.downcast_ref::<DetectError>(), Some(DetectError::NotFound(_))));
+    }
+}
@@ -0,0 +1,235 @@
+//! Checking story files for damage
+//!
+//! Z-code and Glulx headers record the length of the story and a checksum of
+//! its contents, which is enough to catch a truncated or corrupted download.
+//! Every downloaded file also has a SHA-256 hash recorded when it is added, so
+//! later changes can be spotted whatever the format.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::config::GameFormat;
+use crate::detect::{self, story_extent, StoryExtent};
+use crate::inspect::{be16, be32, read_at};
+use crate::storage::LocalGame;
+
+/// Result of checking a story against the length and checksum in its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoryCheck {
+    /// The contents match the header checksum
+    Valid,
+    /// The file is shorter than its header says the story is
+    Truncated { expected: u64, actual: u64 },
+    /// The header checksum does not match the contents
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// The format, or this version of it, has no checksum
+    Unsupported,
+}
+
+impl StoryCheck {
+    /// Whether the story shows signs of damage
+    pub fn is_damaged(&self) -> bool {
+        matches!(self, StoryCheck::Truncated { .. } | StoryCheck::ChecksumMismatch { .. })
+    }
+}
+
+impl fmt::Display for StoryCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoryCheck::Valid => write!(f, "checksum matches"),
+            StoryCheck::Truncated { expected, actual } => {
+                write!(f, "truncated: {} of {} bytes", actual, expected)
+            }
+            StoryCheck::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: header has 0x{:x}, contents give 0x{:x}", expected, actual)
+            }
+            StoryCheck::Unsupported => write!(f, "no checksum in this format"),
+        }
+    }
+}
+
+/// Check a story file, or the story inside a Blorb, against its header
+pub fn check_story(path: &Path) -> Result<StoryCheck> {
+    let format = detect::detect(path)?.format;
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open story file: {}", path.display()))?;
+    let StoryExtent { start, len, .. } = story_extent(&mut file).context("Failed to read story file")?;
+
+    match format {
+        GameFormat::ZCode => Ok(check_zcode(&read_at(&mut file, start, len)?)),
+        GameFormat::Glulx => Ok(check_glulx(&read_at(&mut file, start, len)?)),
+        _ => Ok(StoryCheck::Unsupported),
+    }
+}
+
+/// Z-code: the sum of every byte after the header, modulo 0x10000
+///
+/// Versions 1 and 2 have no length or checksum, and neither do stories whose
+/// length field was left at zero.
+fn check_zcode(story: &[u8]) -> StoryCheck {
+    let (Some(&version), Some(words), Some(expected)) = (story.first(), be16(story, 0x1A), be16(story, 0x1C)) else {
+        return StoryCheck::Unsupported;
+    };
+    let multiplier = match version {
+        3 => 2,
+        4 | 5 => 4,
+        6..=8 => 8,
+        _ => return StoryCheck::Unsupported,
+    };
+    let stated = words as usize * multiplier;
+    if stated == 0 {
+        return StoryCheck::Unsupported;
+    }
+    if stated > story.len() {
+        return StoryCheck::Truncated { expected: stated as u64, actual: story.len() as u64 };
+    }
+
+    let actual = story[64..stated].iter().fold(0u16, |sum, &b| sum.wrapping_add(b as u16));
+    if actual == expected {
+        StoryCheck::Valid
+    } else {
+        StoryCheck::ChecksumMismatch { expected: expected as u32, actual: actual as u32 }
+    }
+}
+
+/// Glulx: the sum of the file as 32-bit words, with the checksum field as zero
+///
+/// The file is exactly as long as the initial contents of memory, which end
+/// where EXTSTART says extended memory begins.
+fn check_glulx(story: &[u8]) -> StoryCheck {
+    let (Some(ext_start), Some(expected)) = (be32(story, 12), be32(story, 32)) else {
+        return StoryCheck::Unsupported;
+    };
+    let ext_start = ext_start as usize;
+    if ext_start > story.len() {
+        return StoryCheck::Truncated { expected: ext_start as u64, actual: story.len() as u64 };
+    }
+
+    let actual = story[..ext_start]
+        .chunks(4)
+        .enumerate()
+        .filter(|&(i, _)| i != 8)
+        .fold(0u32, |sum, (_, word)| {
+            let mut bytes = [0u8; 4];
+            bytes[..word.len()].copy_from_slice(word);
+            sum.wrapping_add(u32::from_be_bytes(bytes))
+        });
+    if actual == expected {
+        StoryCheck::Valid
+    } else {
+        StoryCheck::ChecksumMismatch { expected, actual }
+    }
+}
+
+/// SHA-256 of a file's contents, as lowercase hex
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// State of a downloaded game's file compared with what was recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Unchanged since it was added, and its checksum (if any) matches
+    Ok,
+    /// Undamaged, but added before hashes were recorded
+    Unhashed(String),
+    /// The file has gone
+    Missing,
+    /// The story's own length or checksum shows damage
+    Damaged(StoryCheck),
+    /// The contents differ from the hash recorded when it was added
+    Modified,
+}
+
+/// Re-check a downloaded game's file
+///
+/// A damaged story is reported as such even if its hash also changed, since
+/// that is the more useful thing to know. For an unhashed game the current
+/// hash is returned so it can be recorded.
+pub fn verify(game: &LocalGame) -> Result<Verdict> {
+    if !game.file_path.exists() {
+        return Ok(Verdict::Missing);
+    }
+
+    let check = check_story(&game.file_path)?;
+    if check.is_damaged() {
+        return Ok(Verdict::Damaged(check));
+    }
+
+    let hash = sha256_file(&game.file_path)?;
+    match &game.sha256 {
+        Some(recorded) if *recorded == hash => Ok(Verdict::Ok),
+        Some(_) => Ok(Verdict::Modified),
+        None => Ok(Verdict::Unhashed(hash)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::test_story;
+
+    /// A Glulx story with a correct checksum
+    fn glulx_story() -> Vec<u8> {
+        let mut data = vec![0u8; 512];
+        data[0..4].copy_from_slice(b"Glul");
+        data[4..8].copy_from_slice(&0x0003_0102u32.to_be_bytes());
+        data[8..12].copy_from_slice(&0x100u32.to_be_bytes());
+        data[12..16].copy_from_slice(&512u32.to_be_bytes());
+        data[16..20].copy_from_slice(&1024u32.to_be_bytes());
+        data[300] = 0x42;
+        let checksum = data
+            .chunks(4)
+            .fold(0u32, |sum, w| sum.wrapping_add(u32::from_be_bytes(w.try_into().unwrap())));
+        data[32..36].copy_from_slice(&checksum.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_check_zcode() {
+        assert_eq!(check_zcode(&test_story(5)), StoryCheck::Valid);
+        assert_eq!(check_zcode(&test_story(2)), StoryCheck::Unsupported);
+
+        let mut story = test_story(5);
+        story[0x200] ^= 0xff;
+        assert!(matches!(check_zcode(&story), StoryCheck::ChecksumMismatch { .. }));
+
+        assert_eq!(
+            check_zcode(&test_story(5)[..800]),
+            StoryCheck::Truncated { expected: 1024, actual: 800 }
+        );
+    }
+
+    #[test]
+    fn test_check_glulx() {
+        assert_eq!(check_glulx(&glulx_story()), StoryCheck::Valid);
+
+        let mut story = glulx_story();
+        story[400] = 1;
+        assert!(check_glulx(&story).is_damaged());
+
+        assert_eq!(
+            check_glulx(&glulx_story()[..256]),
+            StoryCheck::Truncated { expected: 512, actual: 256 }
+        );
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(file.path()).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,16 +1,158 @@
-use crate::config::GameFormat;
+use crate::config::{self, GameFormat};
 use crate::detect::*;
+use crate::error::{DetectError, LaunchError};
+use crate::format_choices::FormatChoices;
+use crate::hooks::{self, HookContext, HookExit, HookGame, Hooks};
+use crate::pty;
+use crate::settings::Settings;
+use crate::script::{self, ScriptOptions, ScriptOutcome};
+use crate::supervisor::Supervisor;
+use crate::transcript::{Transcript, TranscriptWriter};
+use crate::web;
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// How to launch one game, replacing what detection and the defaults pick
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchOptions {
+    /// Format to run the game as instead of detecting it
+    #[serde(default)]
+    pub format: Option<GameFormat>,
+    /// Interpreter to run instead of the format's default, by name or path
+    #[serde(default)]
+    pub interpreter: Option<String>,
+    /// Extra arguments placed before the game file
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables for the interpreter
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Commands run around this game, after the global ones
+    #[serde(default)]
+    pub hooks: Hooks,
+}
+
+impl LaunchOptions {
+    /// Whether nothing is overridden
+    pub fn is_empty(&self) -> bool {
+        *self == LaunchOptions::default()
+    }
+
+    /// Split arguments the way a shell would, honouring quotes and backslashes
+    pub fn parse_args(text: &str) -> Result<Vec<String>> {
+        shell_words::split(text).map_err(|e| anyhow!("Invalid arguments: {}", e))
+    }
+
+    /// The arguments as one line, quoted so `parse_args` gives them back
+    pub fn args_string(&self) -> String {
+        shell_words::join(&self.args)
+    }
+
+    /// Parse `NAME=value` pairs separated by spaces, quoted like shell words
+    pub fn parse_env(text: &str) -> Result<BTreeMap<String, String>> {
+        shell_words::split(text)
+            .map_err(|e| anyhow!("Invalid environment: {}", e))?
+            .into_iter()
+            .map(|pair| match pair.split_once('=') {
+                Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+                _ => Err(anyhow!("Environment entries must look like NAME=value, not '{}'", pair)),
+            })
+            .collect()
+    }
+
+    /// The environment as `NAME=value` pairs, quoted so `parse_env` gives them back
+    pub fn env_string(&self) -> String {
+        self.env
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, shell_words::quote(value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
 
 /// Interactive Fiction game launcher
 ///
 /// The Launcher is responsible for detecting game formats and executing
 /// appropriate interpreters for Interactive Fiction games.
+#[derive(Clone)]
 pub struct Launcher {
-    // Could hold configuration or state in the future
+    /// Stop interpreters that run longer than this
+    time_limit: Option<Duration>,
+    /// Per-interpreter replacements for the built-in exit code rules
+    exit_codes: HashMap<String, Vec<i32>>,
+    /// Format to run games as instead of detecting one
+    format_override: Option<GameFormat>,
+    /// Interpreter to run instead of the format's default, by name or path
+    interpreter_override: Option<String>,
+    /// Formats the user picked for files detection could not identify
+    format_choices: FormatChoices,
+    /// Extra interpreter arguments, placed before the game file
+    extra_args: Vec<String>,
+    /// Extra environment variables for the interpreter
+    extra_env: BTreeMap<String, String>,
+    /// Commands run before and after the interpreter
+    hooks: Hooks,
+    /// Library entry being launched, described to hooks
+    game: Option<HookGame>,
+    /// Command that opens web games, if configured
+    browser: Option<String>,
+}
+
+/// What happened when an interpreter was run
+#[derive(Debug, Clone)]
+pub struct LaunchOutcome {
+    /// Name of the interpreter that ran the game
+    pub interpreter: String,
+    /// Format the game was run as
+    pub format: GameFormat,
+    pub started_at: SystemTime,
+    pub ended_at: SystemTime,
+    /// Exit code, or `None` if the interpreter was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Transcript of the session, if one was recorded
+    pub transcript: Option<Transcript>,
+    /// Non-zero exit codes this interpreter uses for a normal exit
+    pub normal_exit_codes: Vec<i32>,
+    /// Signal glkcli received while the interpreter ran
+    pub signal: Option<i32>,
+    /// Whether the interpreter was stopped for reaching the time limit
+    pub timed_out: bool,
+}
+
+impl LaunchOutcome {
+    /// How long the interpreter ran
+    pub fn duration(&self) -> Duration {
+        self.ended_at
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+
+    /// Turn the interpreter's exit status into an error if it indicates failure
+    pub fn check(&self) -> Result<(), LaunchError> {
+        if self.timed_out {
+            return Err(LaunchError::TimedOut);
+        }
+
+        match self.exit_code {
+            Some(0) => Ok(()),
+            Some(code) if self.normal_exit_codes.contains(&code) => Ok(()),
+            Some(code) => Err(LaunchError::ExitCode {
+                interpreter: self.interpreter.clone(),
+                code,
+            }),
+            None => Err(LaunchError::Signaled),
+        }
+    }
+
+    /// Whether glkcli itself was asked to stop while the game ran
+    pub fn termination_requested(&self) -> bool {
+        matches!(self.signal, Some(libc::SIGTERM) | Some(libc::SIGHUP))
+    }
 }
 
 impl Default for Launcher {
@@ -30,7 +172,104 @@ impl Launcher {
     /// let launcher = Launcher::new().unwrap();
     /// ```
     pub fn new() -> Result<Self> {
-        Ok(Launcher {})
+        Ok(Launcher {
+            time_limit: None,
+            exit_codes: HashMap::new(),
+            format_override: None,
+            interpreter_override: None,
+            format_choices: FormatChoices::default(),
+            extra_args: Vec::new(),
+            extra_env: BTreeMap::new(),
+            hooks: Hooks::default(),
+            game: None,
+            browser: None,
+        })
+    }
+
+    /// Creates a Launcher using the time limit, exit code rules, hooks and
+    /// browser from settings, and the formats remembered for undetectable files
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let format_choices = FormatChoices::load().unwrap_or_else(|e| {
+            log::warn!("Ignoring remembered formats: {:#}", e);
+            FormatChoices::default()
+        });
+
+        Ok(Self::new()?
+            .with_time_limit(settings.time_limit())
+            .with_exit_codes(settings.exit_codes.clone())
+            .with_hooks(&settings.hooks)
+            .with_browser(settings.browser.clone())
+            .with_format_choices(format_choices))
+    }
+
+    /// Stop interpreters after `limit` of wall-clock time
+    pub fn with_time_limit(mut self, limit: Option<Duration>) -> Self {
+        self.time_limit = limit;
+        self
+    }
+
+    /// Replace the built-in exit code rules for some interpreters
+    pub fn with_exit_codes(mut self, exit_codes: HashMap<String, Vec<i32>>) -> Self {
+        self.exit_codes = exit_codes;
+        self
+    }
+
+    /// Run games as `format` without detecting it
+    pub fn with_format_override(mut self, format: Option<GameFormat>) -> Self {
+        self.format_override = format;
+        self
+    }
+
+    /// Run `interpreter` instead of the format's default
+    ///
+    /// A bare name is looked up like the built-in interpreters; anything
+    /// containing a `/` is used as a path.
+    pub fn with_interpreter(mut self, interpreter: Option<String>) -> Self {
+        self.interpreter_override = interpreter;
+        self
+    }
+
+    /// Fall back to formats the user picked when detection fails
+    pub fn with_format_choices(mut self, choices: FormatChoices) -> Self {
+        self.format_choices = choices;
+        self
+    }
+
+    /// Apply a game's own launch options on top of any overrides already set
+    pub fn with_launch_options(mut self, options: &LaunchOptions) -> Self {
+        self.format_override = options.format.or(self.format_override);
+        self.interpreter_override = options.interpreter.clone().or(self.interpreter_override);
+        self.extra_args.extend(options.args.iter().cloned());
+        self.extra_env.extend(options.env.clone());
+        self.hooks.extend(&options.hooks);
+        self
+    }
+
+    /// Run `hooks` around each launch, after any already added
+    pub fn with_hooks(mut self, hooks: &Hooks) -> Self {
+        self.hooks.extend(hooks);
+        self
+    }
+
+    /// Open web games with `command`, where `%s` stands for the page
+    pub fn with_browser(mut self, command: Option<String>) -> Self {
+        self.browser = command;
+        self
+    }
+
+    /// Tell hooks which library game is being launched and where its saves go
+    pub fn with_game(mut self, tuid: &str, save_dir: PathBuf) -> Self {
+        self.game = Some(HookGame {
+            tuid: tuid.to_string(),
+            save_dir,
+        });
+        self
+    }
+
+    /// Remember the format, and optionally interpreter, for a file detection
+    /// cannot identify, so later launches of it succeed
+    pub fn remember_format(&mut self, game_path: &Path, format: GameFormat, interpreter: Option<String>) -> Result<()> {
+        self.format_choices.remember(game_path, format, interpreter)
     }
 
     /// Detects the format of a game file
@@ -50,9 +289,8 @@ impl Launcher {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The game file does not exist
-    /// - The file cannot be opened or read
+    /// Returns [`DetectError::NotFound`] if the game file does not exist, or
+    /// [`DetectError::Unreadable`] if it cannot be opened or read.
     ///
     /// # Examples
     ///
@@ -64,92 +302,308 @@ impl Launcher {
     /// let format = launcher.detect_format(Path::new("zork1.z5")).unwrap();
     /// println!("Detected format: {}", format);
     /// ```
-    pub fn detect_format(&self, game_path: &Path) -> Result<GameFormat> {
+    pub fn detect_format(&self, game_path: &Path) -> Result<GameFormat, DetectError> {
+        Ok(self.detect(game_path)?.format)
+    }
+
+    /// Detects the format of a game file, reporting how it was detected
+    ///
+    /// Works like [`Launcher::detect_format`] but also says whether the
+    /// header, a Blorb executable chunk or the extension decided the format,
+    /// and how confident the result is.
+    pub fn detect(&self, game_path: &Path) -> Result<Detection, DetectError> {
         if !game_path.exists() {
-            return Err(anyhow!("Game file does not exist: {}", game_path.display()));
+            return Err(DetectError::NotFound(game_path.to_path_buf()));
         }
 
-        // Try header detection first (most reliable)
-        let format = detect_format_by_header(game_path)
-            .context("Failed to detect format by header")?;
-        
-        if format != GameFormat::Unknown {
-            return Ok(format);
-        }
+        // Header detection first (most reliable), then the extension
+        detect(game_path)
+    }
 
-        // Fall back to extension detection
-        Ok(detect_format_by_extension(game_path))
+    /// Where the interpreter for `format` is installed, if it is
+    pub fn interpreter_path(&self, format: GameFormat) -> Option<PathBuf> {
+        if format == GameFormat::Web {
+            let command_line = web::browser_command_line(self.browser.as_deref());
+            let (program, _) = web::split_command(&command_line, "").ok().flatten()?;
+            return self.find_interpreter_path(&program);
+        }
+        self.find_interpreter_path(format.interpreter()?)
     }
 
-    pub fn detect_and_run(&self, game_path: &Path, verbose: bool) -> Result<()> {
+    pub fn detect_and_run(
+        &self,
+        game_path: &Path,
+        verbose: bool,
+        transcript_dir: Option<&Path>,
+    ) -> Result<LaunchOutcome, LaunchError> {
         if verbose {
             println!("Info: Detecting game format...");
         }
 
-        let format = self.detect_format(game_path)?;
-        if format == GameFormat::Unknown {
-            return Err(anyhow!("Unable to detect game format"));
-        }
+        let outcome = self.launch(game_path, verbose, transcript_dir)?;
+        outcome.check()?;
+
+        Ok(outcome)
+    }
+
+    /// Detect the format and run the game, without judging the exit status
+    ///
+    /// Errors only if the interpreter could not be started at all, or a
+    /// pre-launch hook failed, so callers can record the session before
+    /// deciding how to report the exit status. With a `transcript_dir`, the
+    /// session is recorded there.
+    pub fn launch(
+        &self,
+        game_path: &Path,
+        verbose: bool,
+        transcript_dir: Option<&Path>,
+    ) -> Result<LaunchOutcome, LaunchError> {
+        let (format, interpreter) = self.resolve(game_path)?;
 
         if verbose {
             println!("Info: Detected format: {}", format);
         }
 
-        self.run_game(game_path, format)
-            .context("Failed to run game")
+        if self.hooks.is_empty() {
+            return self.run_game(game_path, format, interpreter.as_deref(), transcript_dir);
+        }
+
+        let game_path = game_path.canonicalize()
+            .with_context(|| format!("Failed to resolve game path: {}", game_path.display()))?;
+        let mut context = HookContext {
+            game_path: &game_path,
+            format,
+            game: self.game.as_ref(),
+            exit: None,
+        };
+        hooks::run_pre_launch(&self.hooks, &context)?;
+
+        // Post-launch hooks undo what pre-launch hooks did, so they run even
+        // when the interpreter could not be started
+        let result = self.run_game(&game_path, format, interpreter.as_deref(), transcript_dir);
+        context.exit = Some(match &result {
+            Ok(outcome) => HookExit {
+                interpreter: &outcome.interpreter,
+                exit_code: outcome.exit_code,
+                duration: outcome.duration(),
+            },
+            Err(_) => HookExit {
+                interpreter: interpreter.as_deref().unwrap_or_default(),
+                exit_code: None,
+                duration: Duration::ZERO,
+            },
+        });
+        hooks::run_post_launch(&self.hooks, &context);
+
+        result
+    }
+
+    /// Run a game as `format`, with `interpreter` or else the format's default
+    pub fn run_game(
+        &self,
+        game_path: &Path,
+        format: GameFormat,
+        interpreter: Option<&str>,
+        transcript_dir: Option<&Path>,
+    ) -> Result<LaunchOutcome, LaunchError> {
+        let (mut cmd, interpreter_name) = self.interpreter_command(game_path, format, interpreter)?;
+        // A browser has nothing worth recording, and usually returns at once
+        let transcript_dir = transcript_dir.filter(|_| format != GameFormat::Web);
+        let interpreter_path = PathBuf::from(cmd.get_program());
+        let spawn_error = |source| LaunchError::Spawn {
+            path: interpreter_path.clone(),
+            source,
+        };
+
+        // Execute the interpreter, passing on signals and enforcing the time limit
+        let started_at = SystemTime::now();
+        let (status, transcript, supervisor) = match transcript_dir {
+            Some(dir) => {
+                // Relay the session through a PTY so it can be recorded
+                let mut supervisor = Supervisor::new(self.time_limit)?;
+                let mut writer = TranscriptWriter::create(dir)?;
+                let mut child = pty::spawn(&mut cmd).map_err(spawn_error)?;
+                let status = pty::relay(&mut child, &mut supervisor, Some(&mut writer))?;
+                (status, Some(writer.finish()?), supervisor)
+            }
+            None => {
+                let mut supervisor = Supervisor::sharing_terminal(self.time_limit)?;
+                let mut child = cmd.spawn().map_err(spawn_error)?;
+                (supervisor.wait(&mut child)?, None, supervisor)
+            }
+        };
+
+        Ok(LaunchOutcome {
+            interpreter: interpreter_name.clone(),
+            format,
+            started_at,
+            ended_at: SystemTime::now(),
+            exit_code: status.code(),
+            transcript,
+            normal_exit_codes: self.normal_exit_codes(&interpreter_name),
+            signal: supervisor.received,
+            timed_out: supervisor.timed_out,
+        })
+    }
+
+    /// Replay a command script into the game under a PTY
+    ///
+    /// Returns the session output as plain text along with the interpreter's
+    /// exit status.
+    pub fn run_script(
+        &self,
+        game_path: &Path,
+        commands: &[String],
+        options: &ScriptOptions,
+    ) -> Result<ScriptOutcome, LaunchError> {
+        let (format, interpreter) = self.resolve(game_path)?;
+
+        let (mut cmd, _) = self.interpreter_command(game_path, format, interpreter.as_deref())?;
+        let mut supervisor = Supervisor::new(self.time_limit)?;
+        let mut child = pty::spawn(&mut cmd).map_err(|source| LaunchError::Spawn {
+            path: PathBuf::from(cmd.get_program()),
+            source,
+        })?;
+
+        Ok(script::replay(&mut child, &mut supervisor, commands, options)?)
+    }
+
+    /// Work out the format and interpreter to run a game with
+    ///
+    /// An override wins, then detection, then a format the user picked for
+    /// this file earlier. A file none of these identify is an error.
+    fn resolve(&self, game_path: &Path) -> Result<(GameFormat, Option<String>), LaunchError> {
+        let mut interpreter = self.interpreter_override.clone();
+
+        let format = match self.format_override {
+            Some(_) if !game_path.exists() => {
+                return Err(DetectError::NotFound(game_path.to_path_buf()).into());
+            }
+            Some(format) => format,
+            None => match self.detect_format(game_path)? {
+                GameFormat::Unknown => {
+                    let choice = self.format_choices
+                        .get(game_path)
+                        .ok_or_else(|| LaunchError::UnknownFormat(game_path.to_path_buf()))?;
+                    interpreter = interpreter.or_else(|| choice.interpreter.clone());
+                    choice.format
+                }
+                format => format,
+            },
+        };
+
+        Ok((format, interpreter))
+    }
+
+    /// Non-zero exit codes that mean a normal exit for an interpreter
+    fn normal_exit_codes(&self, interpreter: &str) -> Vec<i32> {
+        match self.exit_codes.get(interpreter) {
+            Some(codes) => codes.clone(),
+            None => config::normal_exit_codes(interpreter).to_vec(),
+        }
     }
 
-    pub fn run_game(&self, game_path: &Path, format: GameFormat) -> Result<()> {
-        let interpreter_name = format.interpreter()
-            .ok_or_else(|| anyhow!("No interpreter configured for format: {}", format))?;
+    /// Build the command that runs a game with `interpreter`, or the
+    /// interpreter for its format
+    ///
+    /// Also returns the interpreter's name, which selects its exit code rules.
+    fn interpreter_command(
+        &self,
+        game_path: &Path,
+        format: GameFormat,
+        interpreter: Option<&str>,
+    ) -> Result<(Command, String), LaunchError> {
+        if format == GameFormat::Web {
+            return self.browser_command(game_path, interpreter);
+        }
+
+        let interpreter_name = match interpreter {
+            Some(name) => name,
+            None => format.interpreter().ok_or(LaunchError::NoInterpreter(format))?,
+        };
 
         let interpreter_path = self.find_interpreter_path(interpreter_name)
-            .ok_or_else(|| anyhow!("Interpreter '{}' not found", interpreter_name))?;
+            .ok_or_else(|| LaunchError::InterpreterNotFound {
+                interpreter: interpreter_name.to_string(),
+                format,
+            })?;
+
+        // The interpreter runs in the game's directory, so relative paths would break
+        let game_path = game_path.canonicalize()
+            .with_context(|| format!("Failed to resolve game path: {}", game_path.display()))?;
 
         // Change to the game's directory (where save files will be created)
         let game_dir = game_path.parent()
-            .ok_or_else(|| anyhow!("Could not determine game directory"))?;
+            .context("Could not determine game directory")?;
         
         // Build command arguments
         let mut cmd = Command::new(&interpreter_path);
         
-        // Add interpreter-specific flags
-        for flag in format.flags() {
-            cmd.arg(flag);
+        // Add interpreter-specific flags, which only suit the default interpreter
+        if interpreter.is_none() {
+            for flag in format.flags() {
+                cmd.arg(flag);
+            }
         }
+        cmd.args(&self.extra_args);
         
         // Add game file
-        cmd.arg(game_path);
+        cmd.arg(&game_path);
+        cmd.envs(&self.extra_env);
 
         // Set working directory to the game's directory
         cmd.current_dir(game_dir);
 
-        // Execute the interpreter
-        let status = cmd.status()
-            .with_context(|| format!("Failed to execute interpreter: {}", interpreter_path.display()))?;
-
-        // Some interpreters return non-zero exit codes even after successful gameplay
-        // We only treat it as a real error if the exit code indicates a serious problem
-        // (e.g., 127 = command not found, 126 = not executable, negative = killed by signal)
-        if !status.success() {
-            if let Some(code) = status.code() {
-                // Exit codes 1-3 are often used by interpreters for normal gameplay completion
-                // Only treat high error codes or execution failures as real errors
-                if code >= 100 || code < 0 {
-                    return Err(anyhow!("Interpreter exited with error code: {}", code));
-                }
-                // For codes 1-99, we assume the game ran (user might have quit, saved, etc.)
-                // and don't treat it as an error
-            } else {
-                // No exit code (killed by signal?) - this is a real error
-                return Err(anyhow!("Interpreter was terminated by signal"));
-            }
+        let name = Path::new(interpreter_name)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| interpreter_name.to_string());
+
+        Ok((cmd, name))
+    }
+
+    /// Build the command that opens a web game in the browser
+    ///
+    /// A game's own interpreter override replaces the browser command.
+    fn browser_command(&self, game_path: &Path, browser: Option<&str>) -> Result<(Command, String), LaunchError> {
+        let game_path = game_path.canonicalize()
+            .with_context(|| format!("Failed to resolve game path: {}", game_path.display()))?;
+        let target = web::browser_target(&game_path)?;
+
+        let command_line = match browser {
+            Some(command) => command.to_string(),
+            None => web::browser_command_line(self.browser.as_deref()),
+        };
+        let (program, args) = web::split_command(&command_line, &target)?
+            .ok_or(LaunchError::NoInterpreter(GameFormat::Web))?;
+
+        let program_path = self.find_interpreter_path(&program)
+            .ok_or_else(|| LaunchError::InterpreterNotFound {
+                interpreter: program.clone(),
+                format: GameFormat::Web,
+            })?;
+
+        let mut cmd = Command::new(program_path);
+        cmd.args(&self.extra_args).args(args).envs(&self.extra_env);
+        if let Some(dir) = game_path.parent() {
+            cmd.current_dir(dir);
         }
 
-        Ok(())
+        let name = Path::new(&program)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or(program);
+
+        Ok((cmd, name))
     }
 
     fn find_interpreter_path(&self, interpreter_name: &str) -> Option<PathBuf> {
+        // A path rather than a name is used as given
+        if interpreter_name.contains('/') {
+            let path = PathBuf::from(interpreter_name);
+            return path.exists().then_some(path);
+        }
+
         // First check configured installation directory (set at compile time)
         // This is typically /usr/share/glkterm/bin for system installations
         if let Some(install_dir) = option_env!("GLKTERM_BIN_DIR") {
@@ -203,11 +657,7 @@ mod tests {
 
         // Write appropriate header
         let header: Vec<u8> = match format {
-            GameFormat::ZCode => {
-                let mut data = vec![0u8; 32];
-                data[0] = 5; // Z-code version 5
-                data
-            }
+            GameFormat::ZCode => crate::detect::test_story(5),
             GameFormat::Glulx => {
                 let mut data = vec![0u8; 32];
                 data[0..4].copy_from_slice(b"Glul");
@@ -264,10 +714,112 @@ mod tests {
         let launcher = Launcher::new().unwrap();
         let result = launcher.detect_format(Path::new("/nonexistent/game.z5"));
         
-        assert!(result.is_err());
+        assert!(matches!(result, Err(DetectError::NotFound(_))));
         assert!(result.unwrap_err().to_string().contains("does not exist"));
     }
 
+    #[test]
+    fn test_launch_unknown_format() {
+        let launcher = Launcher::new().unwrap();
+        let mut file = NamedTempFile::with_suffix(".txt").unwrap();
+        file.write_all(&[0xFF; 32]).unwrap();
+        file.flush().unwrap();
+
+        let result = launcher.launch(file.path(), false, None);
+        assert!(matches!(result, Err(LaunchError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn test_format_override_and_remembered_choice() {
+        let mut file = NamedTempFile::with_suffix(".txt").unwrap();
+        file.write_all(&[0xFF; 32]).unwrap();
+        file.flush().unwrap();
+
+        let launcher = Launcher::new().unwrap().with_format_override(Some(GameFormat::Hugo));
+        assert_eq!(launcher.resolve(file.path()).unwrap().0, GameFormat::Hugo);
+
+        let mut launcher = Launcher::new().unwrap();
+        launcher.remember_format(file.path(), GameFormat::Glulx, Some("glulxe".to_string())).unwrap();
+        let (format, interpreter) = launcher.resolve(file.path()).unwrap();
+        assert_eq!(format, GameFormat::Glulx);
+        assert_eq!(interpreter.as_deref(), Some("glulxe"));
+
+        // Detection still wins for files it recognises
+        let zcode = create_test_game_file(GameFormat::ZCode);
+        launcher.remember_format(zcode.path(), GameFormat::Glulx, None).unwrap();
+        assert_eq!(launcher.resolve(zcode.path()).unwrap().0, GameFormat::ZCode);
+    }
+
+    #[test]
+    fn test_launch_options_build_command() {
+        let file = create_test_game_file(GameFormat::ZCode);
+        let options = LaunchOptions {
+            format: Some(GameFormat::Glulx),
+            interpreter: Some("/bin/sh".to_string()),
+            args: vec!["-x".to_string()],
+            env: LaunchOptions::parse_env("TERM=dumb LANG=C").unwrap(),
+            ..LaunchOptions::default()
+        };
+        let launcher = Launcher::new().unwrap().with_launch_options(&options);
+
+        let (format, interpreter) = launcher.resolve(file.path()).unwrap();
+        assert_eq!(format, GameFormat::Glulx);
+        let (cmd, name) = launcher.interpreter_command(file.path(), format, interpreter.as_deref()).unwrap();
+        assert_eq!(name, "sh");
+
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0], "-x");
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&("TERM".as_ref(), Some("dumb".as_ref()))));
+        assert!(envs.contains(&("LANG".as_ref(), Some("C".as_ref()))));
+    }
+
+    #[test]
+    fn test_web_game_opens_in_browser() {
+        let temp = TempDir::new().unwrap();
+        let shortcut = temp.path().join("game.url");
+        std::fs::write(&shortcut, web::shortcut_contents("https://example.com/play")).unwrap();
+
+        let launcher = Launcher::new().unwrap().with_browser(Some("/bin/echo --new-tab %s".to_string()));
+        let (format, interpreter) = launcher.resolve(&shortcut).unwrap();
+        assert_eq!(format, GameFormat::Web);
+
+        let (cmd, name) = launcher.interpreter_command(&shortcut, format, interpreter.as_deref()).unwrap();
+        assert_eq!(name, "echo");
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["--new-tab", "https://example.com/play"]);
+
+        let outcome = launcher.launch(&shortcut, false, Some(temp.path())).unwrap();
+        assert_eq!(outcome.format, GameFormat::Web);
+        assert!(outcome.transcript.is_none());
+        assert!(outcome.check().is_ok());
+    }
+
+    #[test]
+    fn test_parse_launch_env() {
+        let env = LaunchOptions::parse_env("  A=1 B=x=y C= ").unwrap();
+        assert_eq!(env.get("B").map(String::as_str), Some("x=y"));
+        assert_eq!(env.get("C").map(String::as_str), Some(""));
+        assert!(LaunchOptions::parse_env("NOVALUE").is_err());
+        assert!(LaunchOptions::parse_env("=1").is_err());
+        assert!(LaunchOptions::parse_env("A='1").is_err());
+        assert!(LaunchOptions::default().is_empty());
+    }
+
+    #[test]
+    fn test_launch_options_with_spaces_roundtrip() {
+        let env = LaunchOptions::parse_env("BOCFEL_OPTS='-x -y' TERM=dumb").unwrap();
+        assert_eq!(env.get("BOCFEL_OPTS").map(String::as_str), Some("-x -y"));
+
+        let args = LaunchOptions::parse_args(r#"-s "My Saves/slot 1" -q"#).unwrap();
+        assert_eq!(args, ["-s", "My Saves/slot 1", "-q"]);
+
+        let options = LaunchOptions { args, env, ..Default::default() };
+        assert_eq!(LaunchOptions::parse_args(&options.args_string()).unwrap(), options.args);
+        assert_eq!(LaunchOptions::parse_env(&options.env_string()).unwrap(), options.env);
+    }
+
     #[test]
     fn test_detect_format_by_extension_fallback() {
         let launcher = Launcher::new().unwrap();
@@ -320,6 +872,43 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_launch_outcome_check() {
+        let outcome = |exit_code| LaunchOutcome {
+            interpreter: "scare".to_string(),
+            format: GameFormat::Adrift,
+            started_at: SystemTime::UNIX_EPOCH,
+            ended_at: SystemTime::UNIX_EPOCH + Duration::from_secs(90),
+            exit_code,
+            transcript: None,
+            normal_exit_codes: vec![1],
+            signal: None,
+            timed_out: false,
+        };
+
+        assert!(outcome(Some(0)).check().is_ok());
+        assert!(outcome(Some(1)).check().is_ok());
+        assert!(matches!(outcome(Some(2)).check(), Err(LaunchError::ExitCode { code: 2, .. })));
+        assert!(outcome(Some(127)).check().is_err());
+        assert!(matches!(outcome(None).check(), Err(LaunchError::Signaled)));
+        assert!(matches!(
+            LaunchOutcome { timed_out: true, ..outcome(Some(0)) }.check(),
+            Err(LaunchError::TimedOut)
+        ));
+        assert_eq!(outcome(Some(0)).duration(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_exit_code_overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert("bocfel".to_string(), vec![4]);
+        let launcher = Launcher::new().unwrap().with_exit_codes(overrides);
+
+        assert_eq!(launcher.normal_exit_codes("bocfel"), vec![4]);
+        assert_eq!(launcher.normal_exit_codes("scare"), vec![1, 2, 3]);
+        assert!(launcher.normal_exit_codes("git").is_empty());
+    }
+
     #[test]
     fn test_game_format_interpreter_mapping() {
         // Verify all supported formats have interpreters except Unknown
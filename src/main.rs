@@ -1,11 +1,19 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 mod launcher;
 mod detect;
+mod inspect;
+mod integrity;
+mod error;
+mod format_choices;
+mod hooks;
 mod config;
 mod ifdb;
+mod cache;
+mod cover_art;
 mod storage;
 mod tui;
 mod network;
@@ -13,6 +21,14 @@ mod app;
 mod ui;
 mod utils;
 mod border_style;
+mod settings;
+mod commands;
+mod pty;
+mod script;
+mod supervisor;
+mod text_input;
+mod transcript;
+mod web;
 
 use launcher::*;
 
@@ -48,15 +64,31 @@ fn setup_debug_logging() -> Result<()> {
     Ok(())
 }
 
+/// Load settings for a command-line launch, warning about a broken file
+fn load_settings() -> settings::Settings {
+    settings::Settings::load().unwrap_or_else(|e| {
+        eprintln!("Warning: {:#}", e);
+        settings::Settings::default()
+    })
+}
+
 #[derive(Parser)]
 #[command(name = "glkcli")]
 #[command(about = "glkterm command-line launcher")]
 #[command(version)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Show detected game format without running
     #[arg(short, long)]
     format: bool,
 
+    /// With --format, print the detection result as JSON
+    #[arg(long, requires = "format")]
+    json: bool,
+
     /// Show additional information
     #[arg(short, long)]
     verbose: bool,
@@ -69,6 +101,22 @@ struct Cli {
     #[arg(long)]
     assume_online: bool,
 
+    /// Stop the interpreter after this many minutes
+    #[arg(long, value_name = "MINUTES")]
+    time_limit: Option<u64>,
+
+    /// Record the session to a transcript in the game's `transcripts` folder
+    #[arg(long)]
+    transcript: bool,
+
+    /// Run the game as this format instead of detecting it (e.g. zcode, glulx)
+    #[arg(long, value_name = "FORMAT")]
+    format_override: Option<config::GameFormat>,
+
+    /// Interpreter to run instead of the format's default, by name or path
+    #[arg(long, value_name = "INTERPRETER")]
+    interpreter: Option<String>,
+
     /// Launch without loading save file (future feature)
     #[arg(long)]
     no_save: bool,
@@ -85,9 +133,171 @@ struct Cli {
     game_file: Option<PathBuf>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Remove game directories, covers and saves no downloaded game references
+    Gc {
+        /// List what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Leave orphaned save directories in place
+        #[arg(long)]
+        keep_saves: bool,
+    },
+
+    /// Show play time and session history for downloaded games
+    Stats {
+        /// Export full session history as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Run a game, optionally replaying a command script into it
+    Run {
+        /// Game file to run
+        game_file: PathBuf,
+
+        /// File with one command per line to feed to the interpreter
+        #[arg(long)]
+        script: Option<PathBuf>,
+
+        /// Transcript the script's output must match (exits non-zero otherwise)
+        #[arg(long, requires = "script")]
+        expect: Option<PathBuf>,
+
+        /// Text the interpreter's prompt ends with
+        #[arg(long, default_value = ">", requires = "script")]
+        prompt: String,
+
+        /// Milliseconds of silence after which the interpreter is assumed to be waiting
+        #[arg(long, default_value_t = 1000, requires = "script")]
+        idle_ms: u64,
+
+        /// Seconds to wait for each prompt before giving up
+        #[arg(long, default_value_t = 30, requires = "script")]
+        timeout: u64,
+
+        /// Run the game as this format instead of detecting it (e.g. zcode, glulx)
+        #[arg(long, value_name = "FORMAT")]
+        format_override: Option<config::GameFormat>,
+
+        /// Interpreter to run instead of the format's default, by name or path
+        #[arg(long, value_name = "INTERPRETER")]
+        interpreter: Option<String>,
+    },
+
+    /// Show the format of game files, and the interpreter that would run them
+    Detect {
+        /// Game files, or directories to search
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check downloaded games for truncated, corrupted or modified files
+    Verify,
+
+    /// Show the metadata in a story file's header, such as its release and serial number
+    Inspect {
+        /// Story file to read
+        file: PathBuf,
+
+        /// Print the fields as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Search IFDB for games
+    Search {
+        /// Words to search for
+        query: Vec<String>,
+
+        /// Only games in this genre
+        #[arg(long)]
+        genre: Option<String>,
+
+        /// Only games written with this development system (e.g. "Inform 7")
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Only games with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only games in this language (e.g. en, de)
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Only games by this author
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Only games rated at least this many stars
+        #[arg(long, value_name = "STARS", value_parser = clap::value_parser!(u8).range(1..=5))]
+        min_rating: Option<u8>,
+
+        /// Only games published in or after this year
+        #[arg(long, value_name = "YEAR")]
+        year_from: Option<u16>,
+
+        /// Only games published in or before this year
+        #[arg(long, value_name = "YEAR")]
+        year_to: Option<u16>,
+
+        /// Only games with this estimated play time (15m, 30m, 1h, 2h, 5h or long)
+        #[arg(long, value_name = "BAND")]
+        play_time: Option<ifdb::PlayTime>,
+
+        /// Only games with a file to download
+        #[arg(long)]
+        downloadable: bool,
+
+        /// Number of results per page
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+
+        /// Page of results to show
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a recorded transcript with terminal escape codes removed
+    Transcript {
+        /// Raw transcript file (`.out`) from a game's transcripts folder
+        file: PathBuf,
+    },
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) if e.use_stderr() => {
+            let _ = e.print();
+            return ExitCode::from(error::EXIT_USAGE);
+        }
+        // --help and --version
+        Err(e) => e.exit(),
+    };
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(error::exit_code(&e))
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
 
     // Set up logging if debug is enabled
     if cli.debug {
@@ -95,6 +305,47 @@ async fn main() -> Result<()> {
         log::info!("Debug logging enabled");
     }
 
+    if let Some(command) = cli.command {
+        return match command {
+            Command::Gc { dry_run, keep_saves } => commands::gc(dry_run, keep_saves),
+            Command::Stats { json } => commands::stats(json),
+            Command::Verify => commands::verify(),
+            Command::Transcript { file } => commands::transcript(&file),
+            Command::Detect { paths, json } => commands::detect(&paths, json),
+            Command::Inspect { file, json } => commands::inspect(&file, json),
+            Command::Search {
+                query, genre, system, tag, language, author, min_rating, year_from, year_to,
+                play_time, downloadable, limit, page, json,
+            } => {
+                let filters = ifdb::SearchFilters {
+                    genre, system, tag, language, author, min_rating, year_from, year_to,
+                    play_time, downloadable,
+                };
+                let options = ifdb::SearchOptions::new(query.join(" "))
+                    .with_limit(limit)
+                    .with_page(page)
+                    .with_filters(filters);
+                commands::search(&options, json).await
+            }
+            Command::Run { game_file, script, expect, prompt, idle_ms, timeout, format_override, interpreter } => {
+                let launcher = Launcher::from_settings(&load_settings())?
+                    .with_format_override(format_override)
+                    .with_interpreter(interpreter);
+
+                let Some(script) = script else {
+                    launcher.detect_and_run(&game_file, false, None)?;
+                    return Ok(());
+                };
+                let options = script::ScriptOptions {
+                    prompt,
+                    idle: std::time::Duration::from_millis(idle_ms),
+                    timeout: std::time::Duration::from_secs(timeout),
+                };
+                commands::run_script(&launcher, &game_file, &script, expect.as_deref(), &options)
+            }
+        };
+    }
+
     // If no game file provided, launch TUI browser
     if cli.game_file.is_none() {
         return tui::run_tui(cli.debug, cli.assume_online).await;
@@ -114,12 +365,16 @@ async fn main() -> Result<()> {
         println!("Note: --no-save option not yet implemented");
     }
 
-    let launcher = Launcher::new()?;
+    let mut settings = load_settings();
+    if let Some(minutes) = cli.time_limit {
+        settings.time_limit_minutes = Some(minutes);
+    }
+    let launcher = Launcher::from_settings(&settings)?
+        .with_format_override(cli.format_override)
+        .with_interpreter(cli.interpreter);
 
     if cli.format {
-        let format = launcher.detect_format(&game_file)?;
-        println!("Detected format: {}", format.name());
-        return Ok(());
+        return commands::format(&launcher, &game_file, cli.json);
     }
 
     if cli.verbose {
@@ -127,8 +382,19 @@ async fn main() -> Result<()> {
         println!("Game file: {}", game_file.display());
     }
 
-    launcher.detect_and_run(&game_file, cli.verbose)
+    let transcript_dir = cli.transcript.then(|| {
+        game_file
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .join("transcripts")
+    });
+
+    let outcome = launcher.detect_and_run(&game_file, cli.verbose, transcript_dir.as_deref())
         .context("Failed to run game")?;
 
+    if let (true, Some(transcript)) = (cli.verbose, &outcome.transcript) {
+        println!("Transcript saved to {}", transcript.output.display());
+    }
+
     Ok(())
 }
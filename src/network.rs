@@ -88,7 +88,7 @@ impl NetworkChecker {
             std::time::Duration::from_millis(500),
             tokio::task::spawn_blocking(|| {
                 Command::new("busctl")
-                    .args(&[
+                    .args([
                         "call",
                         "--system",
                         "net.connman.iwd",
@@ -119,7 +119,7 @@ impl NetworkChecker {
             std::time::Duration::from_millis(500),
             tokio::task::spawn_blocking(|| {
                 Command::new("busctl")
-                    .args(&[
+                    .args([
                         "get-property",
                         "--system",
                         "org.freedesktop.NetworkManager",
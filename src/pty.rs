@@ -0,0 +1,215 @@
+//! Running interpreters under a pseudo-terminal
+//!
+//! Normally the interpreter inherits glkcli's terminal. When a session is
+//! recorded, the interpreter is instead given the slave side of a PTY and
+//! glkcli relays bytes between the real terminal and the master side, so
+//! everything that passes through can be captured.
+
+use anyhow::{Context, Result};
+use crossterm::terminal;
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::pty::{openpty, Winsize};
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+use crate::supervisor::Supervisor;
+use crate::transcript::TranscriptWriter;
+
+/// How long to wait for terminal or interpreter output before checking on the child
+pub(crate) const POLL_INTERVAL_MS: i32 = 100;
+
+/// PTY size used when glkcli itself is not attached to a terminal
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// An interpreter running on the slave side of a PTY
+pub struct PtyChild {
+    pub child: Child,
+    /// Master side of the PTY, connected to the interpreter's terminal
+    pub master: File,
+}
+
+/// Puts the real terminal in raw mode for the duration of a relay
+///
+/// Raw mode is left again when the guard is dropped, including on error
+/// paths, so the terminal is never left unusable.
+pub struct RawModeGuard {
+    enabled: bool,
+}
+
+impl RawModeGuard {
+    pub fn new() -> Result<Self> {
+        // Without a terminal (e.g. under a test harness) there is nothing to change
+        if !io::stdin().is_terminal() {
+            return Ok(RawModeGuard { enabled: false });
+        }
+
+        terminal::enable_raw_mode().context("Failed to enable raw mode")?;
+        Ok(RawModeGuard { enabled: true })
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+/// Spawn a command with a new PTY as its controlling terminal
+///
+/// The PTY starts with the same size as glkcli's own terminal, or 80x24 if
+/// there is none. Errors are those of starting the process, so callers can
+/// report them the same way as a plain `Command::spawn`.
+pub fn spawn(cmd: &mut Command) -> io::Result<PtyChild> {
+    let (cols, rows) = terminal::size().unwrap_or(DEFAULT_SIZE);
+    let winsize = Winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let pty = openpty(&winsize, None).map_err(io::Error::from)?;
+    let slave = File::from(pty.slave);
+
+    cmd.stdin(slave.try_clone()?)
+        .stdout(slave.try_clone()?)
+        .stderr(slave);
+
+    // SAFETY: only async-signal-safe libc calls are made between fork and exec
+    unsafe {
+        cmd.pre_exec(|| {
+            // Start a new session so the PTY becomes the controlling terminal
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = cmd.spawn();
+
+    // The command keeps its copies of the slave side open; close them so the
+    // master sees end-of-file once the interpreter exits
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    Ok(PtyChild {
+        child: child?,
+        master: File::from(pty.master),
+    })
+}
+
+/// Relay the real terminal to the interpreter until it exits
+///
+/// Interpreter output is echoed to stdout and player input forwarded to the
+/// interpreter; both are written to `transcript` if one is given. The raw
+/// mode of the real terminal is restored however the relay ends.
+pub fn relay(
+    pty: &mut PtyChild,
+    supervisor: &mut Supervisor,
+    mut transcript: Option<&mut TranscriptWriter>,
+) -> Result<ExitStatus> {
+    let _raw_mode = RawModeGuard::new()?;
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut buf = [0u8; 4096];
+    let mut stdin_open = true;
+
+    loop {
+        supervisor.check(&pty.child);
+
+        let (output_ready, input_ready) = {
+            let mut fds = vec![PollFd::new(&pty.master, PollFlags::POLLIN)];
+            if stdin_open {
+                fds.push(PollFd::new(&stdin, PollFlags::POLLIN));
+            }
+
+            match poll(&mut fds, POLL_INTERVAL_MS) {
+                Ok(_) => {}
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e).context("Failed to wait for terminal input"),
+            }
+
+            (is_ready(&fds[0]), fds.get(1).map(is_ready).unwrap_or(false))
+        };
+
+        if output_ready {
+            match pty.master.read(&mut buf) {
+                // EIO means the interpreter side of the PTY has been closed
+                Ok(0) => break,
+                Err(e) if e.raw_os_error() == Some(libc::EIO) => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e).context("Failed to read interpreter output"),
+                Ok(n) => {
+                    stdout.write_all(&buf[..n]).context("Failed to write to terminal")?;
+                    stdout.flush().context("Failed to write to terminal")?;
+                    if let Some(t) = transcript.as_deref_mut() {
+                        t.record_output(&buf[..n])?;
+                    }
+                }
+            }
+        }
+
+        if input_ready {
+            // Read the descriptor directly - Stdin's buffer would hide data from poll
+            match nix::unistd::read(libc::STDIN_FILENO, &mut buf) {
+                Ok(0) => stdin_open = false,
+                Ok(n) => {
+                    pty.master.write_all(&buf[..n]).context("Failed to send input to interpreter")?;
+                    if let Some(t) = transcript.as_deref_mut() {
+                        t.record_input(&buf[..n])?;
+                    }
+                }
+                Err(Errno::EINTR) => {}
+                Err(e) => return Err(e).context("Failed to read terminal input"),
+            }
+        }
+
+        // Background processes can keep the PTY open after the interpreter exits
+        if !output_ready && !input_ready && pty.child.try_wait()?.is_some() {
+            break;
+        }
+    }
+
+    pty.child.wait().context("Failed to wait for interpreter")
+}
+
+pub(crate) fn is_ready(fd: &PollFd) -> bool {
+    fd.revents()
+        .map(|r| r.intersects(PollFlags::POLLIN | PollFlags::POLLHUP | PollFlags::POLLERR))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_relay_records_output() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = TranscriptWriter::create(temp.path()).unwrap();
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'West of House\\n'; [ -t 1 ] && echo tty"]);
+
+        let mut pty = spawn(&mut cmd).unwrap();
+        let mut supervisor = Supervisor::new(None).unwrap();
+        let status = relay(&mut pty, &mut supervisor, Some(&mut writer)).unwrap();
+        assert!(status.success());
+
+        let transcript = writer.finish().unwrap();
+        let text = transcript.to_plain_text().unwrap();
+        assert!(text.contains("West of House"));
+        assert!(text.contains("tty"));
+    }
+}
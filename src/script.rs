@@ -0,0 +1,240 @@
+//! Replaying command scripts into interpreters
+//!
+//! Used by `glkcli run --script` to drive a game non-interactively: each
+//! command is sent once the interpreter shows its prompt (or goes quiet),
+//! and the whole session is captured so it can be compared with a known-good
+//! transcript.
+
+use anyhow::{anyhow, Context, Result};
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::pty::{self, PtyChild};
+use crate::supervisor::Supervisor;
+use crate::transcript::strip_ansi;
+
+/// How a script is fed to the interpreter
+#[derive(Debug, Clone)]
+pub struct ScriptOptions {
+    /// Text the interpreter's prompt ends with
+    pub prompt: String,
+    /// Silence after which the interpreter is assumed to be waiting anyway
+    pub idle: Duration,
+    /// Longest time to wait for a prompt before giving up
+    pub timeout: Duration,
+}
+
+impl Default for ScriptOptions {
+    fn default() -> Self {
+        ScriptOptions {
+            prompt: ">".to_string(),
+            idle: Duration::from_millis(1000),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Result of replaying a script
+#[derive(Debug)]
+pub struct ScriptOutcome {
+    /// Everything the interpreter printed, as plain text
+    pub transcript: String,
+    /// Exit code, or `None` if the interpreter had to be stopped or was killed
+    pub exit_code: Option<i32>,
+}
+
+/// Read a script file: one command per line, `#` starts a comment line
+///
+/// Blank lines are kept, since pressing Enter on its own is often needed to
+/// get past "[MORE]" prompts.
+pub fn load_commands(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read script: {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect())
+}
+
+/// Send `commands` to the interpreter one prompt at a time
+pub fn replay(
+    pty: &mut PtyChild,
+    supervisor: &mut Supervisor,
+    commands: &[String],
+    options: &ScriptOptions,
+) -> Result<ScriptOutcome> {
+    let mut output = Vec::new();
+
+    for command in commands {
+        if !wait_for_prompt(pty, supervisor, &mut output, options)? {
+            break;
+        }
+
+        pty.master
+            .write_all(format!("{}\r", command).as_bytes())
+            .context("Failed to send command to interpreter")?;
+    }
+
+    // Collect the response to the last command; the interpreter may also have
+    // quit by itself
+    let exited = !wait_for_prompt(pty, supervisor, &mut output, options)?;
+
+    let exit_code = if exited || pty.child.try_wait()?.is_some() {
+        pty.child.wait().context("Failed to wait for interpreter")?.code()
+    } else {
+        // Still waiting for input - the script is over, so stop it
+        let _ = pty.child.kill();
+        let _ = pty.child.wait();
+        None
+    };
+
+    Ok(ScriptOutcome {
+        transcript: strip_ansi(&output),
+        exit_code,
+    })
+}
+
+/// Read output until the prompt appears or the interpreter goes quiet
+///
+/// Returns `false` if the interpreter exited instead.
+fn wait_for_prompt(
+    pty: &mut PtyChild,
+    supervisor: &mut Supervisor,
+    output: &mut Vec<u8>,
+    options: &ScriptOptions,
+) -> Result<bool> {
+    let started = Instant::now();
+    let mut last_output = Instant::now();
+    let response_start = output.len();
+    let mut buf = [0u8; 4096];
+
+    loop {
+        supervisor.check(&pty.child);
+
+        let ready = {
+            let mut fds = [PollFd::new(&pty.master, PollFlags::POLLIN)];
+            match poll(&mut fds, pty::POLL_INTERVAL_MS) {
+                Ok(_) => pty::is_ready(&fds[0]),
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e).context("Failed to wait for interpreter output"),
+            }
+        };
+
+        if ready {
+            match pty.master.read(&mut buf) {
+                Ok(0) => return Ok(false),
+                Err(e) if e.raw_os_error() == Some(libc::EIO) => return Ok(false),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e).context("Failed to read interpreter output"),
+                Ok(n) => {
+                    output.extend_from_slice(&buf[..n]);
+                    last_output = Instant::now();
+                    continue;
+                }
+            }
+        }
+
+        // Nothing new arrived during the last poll interval
+        let response = strip_ansi(&output[response_start..]);
+        if output.len() > response_start && response.trim_end().ends_with(options.prompt.as_str()) {
+            return Ok(true);
+        }
+
+        if last_output.elapsed() >= options.idle {
+            if pty.child.try_wait()?.is_some() {
+                return Ok(false);
+            }
+            return Ok(true);
+        }
+
+        if started.elapsed() >= options.timeout {
+            return Err(anyhow!("Timed out waiting for the interpreter's prompt"));
+        }
+    }
+}
+
+/// Compare a transcript with the expected text
+///
+/// Trailing whitespace and blank lines are ignored. Returns a description of
+/// the first difference, or `None` if they match.
+pub fn compare_transcripts(actual: &str, expected: &str) -> Option<String> {
+    let normalise = |text: &str| -> Vec<String> {
+        let mut lines: Vec<String> = strip_ansi(text.as_bytes())
+            .lines()
+            .map(|l| l.trim_end().to_string())
+            .collect();
+        while lines.last().map(|l| l.is_empty()).unwrap_or(false) {
+            lines.pop();
+        }
+        lines
+    };
+
+    let actual = normalise(actual);
+    let expected = normalise(expected);
+
+    for i in 0..actual.len().max(expected.len()) {
+        let (a, e) = (actual.get(i), expected.get(i));
+        if a != e {
+            return Some(format!(
+                "line {}:\n  expected: {}\n  actual:   {}",
+                i + 1,
+                e.map(String::as_str).unwrap_or("<end of transcript>"),
+                a.map(String::as_str).unwrap_or("<end of transcript>"),
+            ));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_commands_skips_comments() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("walkthrough.txt");
+        fs::write(&path, "# opening\nopen mailbox\r\n\nread leaflet\n").unwrap();
+
+        let commands = load_commands(&path).unwrap();
+        assert_eq!(commands, vec!["open mailbox", "", "read leaflet"]);
+    }
+
+    #[test]
+    fn test_compare_transcripts() {
+        assert!(compare_transcripts("West of House  \n>\n\n", "West of House\n>").is_none());
+
+        let diff = compare_transcripts("West of House\n>north", "West of House\n>south").unwrap();
+        assert!(diff.starts_with("line 2"));
+        assert!(diff.contains(">south") && diff.contains(">north"));
+
+        assert!(compare_transcripts("one", "one\ntwo").unwrap().contains("<end of transcript>"));
+    }
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_replay_waits_for_prompts() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo Welcome; printf '>'; while read line; do echo \"You said: $line\"; printf '>'; done"]);
+
+        let mut pty = pty::spawn(&mut cmd).unwrap();
+        let commands = vec!["look".to_string(), "wait".to_string()];
+        let mut supervisor = Supervisor::new(None).unwrap();
+        let outcome = replay(&mut pty, &mut supervisor, &commands, &ScriptOptions::default()).unwrap();
+
+        assert!(outcome.transcript.contains("You said: look"));
+        assert!(outcome.transcript.contains("You said: wait"));
+        // The interpreter was still waiting for input, so it had to be stopped
+        assert_eq!(outcome.exit_code, None);
+    }
+}
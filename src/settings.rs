@@ -0,0 +1,124 @@
+//! User settings
+//!
+//! Settings are read from `~/.glkcli/settings.json`. Every field is optional
+//! and falls back to a sensible default, so a missing file is the same as an
+//! empty one. Some settings can also be overridden by environment variables,
+//! which take precedence over the file.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::hooks::Hooks;
+
+/// How long deleted games stay in the trash when not configured
+const DEFAULT_TRASH_RETENTION_DAYS: u64 = 7;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// How to draw cover art: "auto", "kitty", "iterm2", "sixel",
+    /// "halfblocks", "ascii" or "none" (overridden by `GLKCLI_COVER_ART`)
+    pub cover_art: Option<String>,
+    /// Days a deleted game stays in `~/.glkcli/trash` before it is purged
+    pub trash_retention_days: Option<u64>,
+    /// Record a transcript of every session started from the TUI
+    pub transcripts: Option<bool>,
+    /// Stop interpreters after this many minutes (no limit by default)
+    pub time_limit_minutes: Option<u64>,
+    /// Non-zero exit codes that mean a normal exit, keyed by interpreter name,
+    /// replacing the built-in rules for that interpreter
+    pub exit_codes: HashMap<String, Vec<i32>>,
+    /// Commands run before and after every game
+    pub hooks: Hooks,
+    /// Command that opens web games, with `%s` for the page or URL
+    /// (overridden by `GLKCLI_BROWSER`; `$BROWSER` or the desktop's
+    /// opener when unset)
+    pub browser: Option<String>,
+    /// Address of the IFDB to search and download from, for a mirror or a
+    /// local stand-in (overridden by `GLKCLI_IFDB_URL`)
+    pub ifdb_url: Option<String>,
+}
+
+impl Settings {
+    /// Location of the settings file
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .ok_or_else(|| anyhow!("Could not determine home directory"))?
+            .join(".glkcli")
+            .join("settings.json"))
+    }
+
+    /// Load settings from `~/.glkcli/settings.json`
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load settings from a specific file, using defaults if it doesn't exist
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse settings file: {}", path.display()))
+    }
+
+    /// Whether sessions started from the TUI are recorded
+    pub fn transcripts_enabled(&self) -> bool {
+        self.transcripts.unwrap_or(false)
+    }
+
+    /// Wall-clock limit for interpreter sessions
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit_minutes.map(|m| Duration::from_secs(m * 60))
+    }
+
+    /// How long deleted games can still be restored
+    pub fn trash_retention(&self) -> Duration {
+        let days = self.trash_retention_days.unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+        Duration::from_secs(days * 24 * 60 * 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_file_gives_defaults() {
+        let temp = TempDir::new().unwrap();
+        let settings = Settings::load_from(&temp.path().join("settings.json")).unwrap();
+        assert!(settings.cover_art.is_none());
+        assert_eq!(settings.trash_retention(), Duration::from_secs(7 * 24 * 60 * 60));
+    }
+
+    #[test]
+    fn test_partial_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.json");
+        fs::write(&path, r#"{ "cover_art": "ascii", "trash_retention_days": 1, "exit_codes": { "bocfel": [1] } }"#).unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+        assert_eq!(settings.cover_art.as_deref(), Some("ascii"));
+        assert_eq!(settings.trash_retention(), Duration::from_secs(24 * 60 * 60));
+        assert_eq!(settings.exit_codes["bocfel"], vec![1]);
+        assert!(settings.time_limit().is_none());
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        assert!(Settings::load_from(&path).is_err());
+    }
+}
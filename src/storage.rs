@@ -4,17 +4,30 @@ use std::collections::HashMap;
 use std::fmt;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use zip::ZipArchive;
 
-use crate::ifdb::{Game, GameDetails};
+use crate::config::GameFormat;
+use crate::detect;
+use crate::error::StorageError;
+use crate::ifdb::{self, Game, GameDetails};
+use crate::inspect;
+use crate::integrity;
+use crate::launcher::LaunchOptions;
+use crate::utils::format_size;
+
+/// Name of the IFDB details snapshot stored in each per-game directory
+const DETAILS_FILE_NAME: &str = "ifdb.json";
 
 /// Manages local storage of downloaded games, metadata, and save files
 pub struct GameStorage {
     base_dir: PathBuf,
     games_dir: PathBuf,
     saves_dir: PathBuf,
+    trash_dir: PathBuf,
     metadata_file: PathBuf,
+    /// IFDB address cover art is fetched from when a game has no cover link
+    ifdb_url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +43,80 @@ pub struct LocalGame {
     pub last_played: Option<SystemTime>,
     pub description: Option<String>,
     pub cover_art_path: Option<PathBuf>,
+    /// Snapshot of the full IFDB details taken when the game was added
+    #[serde(default)]
+    pub details_path: Option<PathBuf>,
+    /// Every recorded play session, oldest first
+    #[serde(default)]
+    pub sessions: Vec<PlaySession>,
+    /// Build read from the story file header, e.g. "Release 88 / 840726"
+    #[serde(default)]
+    pub release: Option<String>,
+    /// Whether the story file has been read for its release, found or not
+    #[serde(default)]
+    pub release_checked: bool,
+    /// SHA-256 of the game file when it was added, as lowercase hex
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Format, interpreter, arguments and environment pinned for this game
+    #[serde(default)]
+    pub launch_options: LaunchOptions,
+}
+
+impl LocalGame {
+    /// Total time spent across all recorded sessions
+    pub fn total_play_time(&self) -> Duration {
+        self.sessions.iter().map(|s| s.duration()).sum()
+    }
+
+    /// The most recent recorded session
+    pub fn last_session(&self) -> Option<&PlaySession> {
+        self.sessions.last()
+    }
+
+    /// Whether the game opens in a browser rather than an interpreter
+    ///
+    /// Judged by extension so lists can show it without reading the file.
+    pub fn is_web(&self) -> bool {
+        match self.launch_options.format {
+            Some(format) => format == GameFormat::Web,
+            None => detect::detect_format_by_extension(&self.file_path) == GameFormat::Web,
+        }
+    }
+}
+
+/// One run of a game's interpreter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaySession {
+    pub started_at: SystemTime,
+    pub ended_at: SystemTime,
+    pub duration_secs: u64,
+    pub interpreter: String,
+    pub exit_code: Option<i32>,
+    /// Save files written or updated during the session
+    #[serde(default)]
+    pub saves_created: Vec<PathBuf>,
+    /// Raw output transcript, if the session was recorded
+    #[serde(default)]
+    pub transcript: Option<PathBuf>,
+}
+
+impl PlaySession {
+    pub fn new(interpreter: &str, started_at: SystemTime, ended_at: SystemTime, exit_code: Option<i32>) -> Self {
+        PlaySession {
+            started_at,
+            ended_at,
+            duration_secs: ended_at.duration_since(started_at).unwrap_or_default().as_secs(),
+            interpreter: interpreter.to_string(),
+            exit_code,
+            saves_created: Vec::new(),
+            transcript: None,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration_secs)
+    }
 }
 
 impl fmt::Display for LocalGame {
@@ -75,8 +162,14 @@ impl GameStorage {
             .ok_or_else(|| anyhow!("Could not determine home directory"))?
             .join(".glkcli");
 
+        Self::with_base_dir(base_dir)
+    }
+
+    /// Create storage rooted at an arbitrary directory instead of `~/.glkcli`
+    pub fn with_base_dir(base_dir: PathBuf) -> Result<Self> {
         let games_dir = base_dir.join("games");
         let saves_dir = base_dir.join("saves");
+        let trash_dir = base_dir.join("trash");
         let metadata_file = base_dir.join("metadata.json");
 
         // Create directories if they don't exist
@@ -88,10 +181,18 @@ impl GameStorage {
             base_dir,
             games_dir,
             saves_dir,
+            trash_dir,
             metadata_file,
+            ifdb_url: ifdb::DEFAULT_BASE_URL.to_string(),
         })
     }
 
+    /// Fetch cover art from an IFDB mirror instead of ifdb.org
+    pub fn with_ifdb_url(mut self, url: &str) -> Self {
+        self.ifdb_url = url.to_string();
+        self
+    }
+
     /// Load metadata from disk
     pub fn load_metadata(&self) -> Result<StorageMetadata> {
         if !self.metadata_file.exists() {
@@ -106,7 +207,10 @@ impl GameStorage {
             .context("Failed to read metadata file")?;
 
         let metadata: StorageMetadata = serde_json::from_str(&content)
-            .context("Failed to parse metadata file")?;
+            .map_err(|source| StorageError::CorruptMetadata {
+                path: self.metadata_file.clone(),
+                source,
+            })?;
 
         Ok(metadata)
     }
@@ -147,7 +251,6 @@ impl GameStorage {
     }
 
     /// Get a specific downloaded game
-    #[allow(dead_code)]
     pub fn get_game(&self, tuid: &str) -> Result<Option<LocalGame>> {
         let metadata = self.load_metadata()?;
         Ok(metadata.games.get(tuid).cloned())
@@ -168,6 +271,10 @@ impl GameStorage {
     }
 
     /// Detect IF game files in a directory by known extensions
+    ///
+    /// Archives with no story file but a web page, such as ink and Twine
+    /// exports, are web games: their `index.html`, or else any top-level
+    /// page, is used instead.
     fn find_if_file_in_dir(&self, dir: &Path) -> Result<Option<PathBuf>> {
         // Known IF file extensions (in priority order)
         let if_extensions = [
@@ -199,7 +306,17 @@ impl GameStorage {
             }
         }
 
-        Ok(None)
+        let index = dir.join("index.html");
+        if index.is_file() {
+            return Ok(Some(index));
+        }
+        let page = read_dir_sorted(dir)?.into_iter().find(|path| {
+            path.is_file() && path.extension().and_then(|e| e.to_str()).is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm")
+            })
+        });
+
+        Ok(page)
     }
 
     /// Detect if file is a ZIP archive
@@ -228,6 +345,15 @@ impl GameStorage {
         fs::write(&file_path, file_data)
             .context("Failed to write game file")?;
 
+        let details_path = match game_details {
+            Some(details) => {
+                let path = file_path.with_extension(DETAILS_FILE_NAME);
+                Self::write_details_snapshot(&path, details)?;
+                Some(path)
+            }
+            None => None,
+        };
+
         // Download cover art if available
         let cover_art_path = if let Some(_cover_url) = &game.cover_art_link {
             // Note: This would need to be called from an async context
@@ -240,6 +366,8 @@ impl GameStorage {
             None
         };
 
+        let release = story_release(&file_path);
+        let sha256 = integrity::sha256_file(&file_path).ok();
         let local_game = LocalGame {
             tuid: game.tuid.clone(),
             title: game.title.clone(),
@@ -256,6 +384,12 @@ impl GameStorage {
                 .and_then(|d| d.bibliographic.as_ref())
                 .and_then(|b| b.description.clone()),
             cover_art_path,
+            details_path,
+            sessions: Vec::new(),
+            release,
+            release_checked: true,
+            sha256,
+            launch_options: LaunchOptions::default(),
         };
 
         // Add to metadata
@@ -319,7 +453,7 @@ impl GameStorage {
 
             // Find the IF file in the extracted directory
             if_file_path = self.find_if_file_in_dir(&game_dir)?
-                .ok_or_else(|| anyhow!("No interactive fiction file found in ZIP archive"))?;
+                .ok_or(StorageError::NoGameInArchive)?;
         } else {
             // Not a ZIP - save file directly to game directory
             let filename = format!("{}.{}", safe_title, file_extension);
@@ -329,6 +463,16 @@ impl GameStorage {
                 .context("Failed to write game file")?;
         }
 
+        // Keep the full IFDB record next to the game for offline use
+        let details_path = match game_details {
+            Some(details) => {
+                let path = game_dir.join(DETAILS_FILE_NAME);
+                Self::write_details_snapshot(&path, details)?;
+                Some(path)
+            }
+            None => None,
+        };
+
         // Download cover art if available
         let cover_art_path = if let Some(cover_url) = &game.cover_art_link {
             match self.download_cover_art(&game.tuid, cover_url).await {
@@ -341,7 +485,7 @@ impl GameStorage {
         } else if game.has_cover_art.unwrap_or(false) {
             // Game has cover art but no direct link in search results
             // Try to construct cover art URL
-            let cover_url = format!("https://ifdb.org/coverart?id={}", game.tuid);
+            let cover_url = format!("{}/coverart?id={}", self.ifdb_url, game.tuid);
             match self.download_cover_art(&game.tuid, &cover_url).await {
                 Ok(path) => Some(path),
                 Err(e) => {
@@ -353,6 +497,8 @@ impl GameStorage {
             None
         };
 
+        let release = story_release(&if_file_path);
+        let sha256 = integrity::sha256_file(&if_file_path).ok();
         let local_game = LocalGame {
             tuid: game.tuid.clone(),
             title: game.title.clone(),
@@ -369,6 +515,12 @@ impl GameStorage {
                 .and_then(|d| d.bibliographic.as_ref())
                 .and_then(|b| b.description.clone()),
             cover_art_path,
+            details_path,
+            sessions: Vec::new(),
+            release,
+            release_checked: true,
+            sha256,
+            launch_options: LaunchOptions::default(),
         };
 
         // Add to metadata
@@ -378,6 +530,115 @@ impl GameStorage {
         Ok(local_game)
     }
 
+    /// Write an IFDB details snapshot to disk
+    fn write_details_snapshot(path: &Path, details: &GameDetails) -> Result<()> {
+        let content = serde_json::to_string_pretty(details)
+            .context("Failed to serialize game details")?;
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write game details: {}", path.display()))
+    }
+
+    /// Load the IFDB details snapshot saved with a downloaded game
+    ///
+    /// Returns `None` for games added before snapshots were kept.
+    pub fn load_game_details(&self, game: &LocalGame) -> Result<Option<GameDetails>> {
+        let Some(path) = &game.details_path else {
+            return Ok(None);
+        };
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read game details: {}", path.display()))?;
+
+        let details = serde_json::from_str(&content)
+            .context("Failed to parse game details")?;
+
+        Ok(Some(details))
+    }
+
+    /// Store a fresh IFDB details snapshot for a downloaded game
+    ///
+    /// Used to backfill games added before snapshots existed and to keep
+    /// existing snapshots current when the details are viewed online.
+    pub fn update_game_details(&self, tuid: &str, details: &GameDetails) -> Result<()> {
+        let mut metadata = self.load_metadata()?;
+
+        let Some(game) = metadata.games.get_mut(tuid) else {
+            return Ok(());
+        };
+
+        let path = match &game.details_path {
+            Some(path) => path.clone(),
+            None => match game.file_path.parent() {
+                Some(dir) if dir != self.games_dir => dir.join(DETAILS_FILE_NAME),
+                _ => game.file_path.with_extension(DETAILS_FILE_NAME),
+            },
+        };
+
+        Self::write_details_snapshot(&path, details)?;
+
+        if game.details_path.is_none() {
+            game.details_path = Some(path);
+            self.save_metadata(&metadata)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the release of games added before it was recorded
+    ///
+    /// Returns how many games a release was found for. Each game's file is
+    /// only read once; games whose header holds no release are marked as
+    /// checked so later calls skip them.
+    pub fn fill_release_info(&self) -> Result<usize> {
+        let mut metadata = self.load_metadata()?;
+
+        let mut checked = 0;
+        let mut updated = 0;
+        for game in metadata.games.values_mut().filter(|g| !g.release_checked) {
+            if game.release.is_none() {
+                game.release = story_release(&game.file_path);
+                updated += game.release.is_some() as usize;
+            }
+            game.release_checked = true;
+            checked += 1;
+        }
+
+        if checked > 0 {
+            self.save_metadata(&metadata)?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Record the hash of a game added before hashes were kept
+    pub fn record_hash(&self, tuid: &str, sha256: &str) -> Result<()> {
+        let mut metadata = self.load_metadata()?;
+
+        let Some(game) = metadata.games.get_mut(tuid) else {
+            return Err(StorageError::GameNotFound(tuid.to_string()).into());
+        };
+        game.sha256 = Some(sha256.to_string());
+
+        self.save_metadata(&metadata)
+    }
+
+    /// Replace the launch options pinned for a game
+    pub fn set_launch_options(&self, tuid: &str, options: LaunchOptions) -> Result<()> {
+        let mut metadata = self.load_metadata()?;
+
+        let Some(game) = metadata.games.get_mut(tuid) else {
+            return Err(StorageError::GameNotFound(tuid.to_string()).into());
+        };
+        game.launch_options = options;
+
+        self.save_metadata(&metadata)
+    }
+
     /// Download and save cover art
     async fn download_cover_art(&self, tuid: &str, cover_url: &str) -> Result<PathBuf> {
         let client = reqwest::Client::builder()
@@ -421,53 +682,318 @@ impl GameStorage {
         Ok(cover_path)
     }
 
-    /// Remove a game from storage
-    #[allow(dead_code)]
-    pub fn remove_game(&self, tuid: &str) -> Result<()> {
+    /// Move a game and everything belonging to it into the trash
+    ///
+    /// The game disappears from the library but can be brought back with
+    /// `restore_from_trash` until the entry is purged.
+    pub fn trash_game(&self, tuid: &str) -> Result<TrashEntry> {
         let mut metadata = self.load_metadata()?;
 
-        if let Some(game) = metadata.games.remove(tuid) {
-            // Remove game file
-            if game.file_path.exists() {
-                fs::remove_file(&game.file_path)
-                    .context("Failed to remove game file")?;
+        let Some(game) = metadata.games.get(tuid).cloned() else {
+            return Err(StorageError::GameNotFound(tuid.to_string()).into());
+        };
+
+        let trashed_at = SystemTime::now();
+        let entry_dir = self.create_trash_entry_dir(tuid, trashed_at)?;
+
+        let mut originals = Vec::new();
+        match self.game_root(&game.file_path) {
+            Some(dir) => originals.push(dir),
+            None => originals.push(game.file_path.clone()),
+        }
+        originals.extend(game.details_path.clone());
+        originals.extend(game.cover_art_path.clone());
+        originals.push(self.get_save_dir(tuid));
+
+        let mut items = Vec::new();
+        for original in originals {
+            // The details snapshot usually lives inside the game directory
+            if !original.exists() || items.iter().any(|i: &TrashedItem| original.starts_with(&i.original)) {
+                continue;
             }
 
-            // Remove cover art if it exists
-            if let Some(cover_path) = &game.cover_art_path {
-                if cover_path.exists() {
-                    fs::remove_file(cover_path)
-                        .context("Failed to remove cover art")?;
-                }
+            let stored = format!("{}", items.len());
+            if let Err(e) = fs::rename(&original, entry_dir.join(&stored)) {
+                roll_back_trash(&entry_dir, &items);
+                return Err(e).with_context(|| format!("Failed to move {} to trash", original.display()));
             }
+            items.push(TrashedItem { original, stored });
+        }
 
-            // Remove associated save files
-            if let Some(saves) = metadata.saves.remove(tuid) {
-                for save in saves {
-                    if save.file_path.exists() {
-                        fs::remove_file(&save.file_path)
-                            .context("Failed to remove save file")?;
-                    }
-                }
+        let entry = TrashEntry {
+            id: entry_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            trashed_at,
+            saves: metadata.saves.remove(tuid).unwrap_or_default(),
+            game,
+            items,
+        };
+
+        let written = serde_json::to_string_pretty(&entry)
+            .context("Failed to serialize trash entry")
+            .and_then(|content| {
+                fs::write(entry_dir.join(TRASH_ENTRY_FILE_NAME), content)
+                    .context("Failed to write trash entry")
+            })
+            .and_then(|_| {
+                metadata.games.remove(tuid);
+                self.save_metadata(&metadata)
+            });
+        if let Err(e) = written {
+            roll_back_trash(&entry_dir, &entry.items);
+            return Err(e);
+        }
+
+        Ok(entry)
+    }
+
+    /// Create a new, empty directory for a trash entry
+    ///
+    /// Entries are named after the game and the time it was deleted, with a
+    /// counter added when the same game is deleted twice within a second.
+    fn create_trash_entry_dir(&self, tuid: &str, trashed_at: SystemTime) -> Result<PathBuf> {
+        fs::create_dir_all(&self.trash_dir).context("Failed to create trash directory")?;
+
+        let secs = trashed_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let base = format!("{}-{}", sanitize_component(tuid), secs);
+
+        for attempt in 0.. {
+            let name = match attempt {
+                0 => base.clone(),
+                n => format!("{}-{}", base, n),
+            };
+            let dir = self.trash_dir.join(name);
+            match fs::create_dir(&dir) {
+                Ok(()) => return Ok(dir),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e).context("Failed to create trash directory"),
+            }
+        }
+
+        unreachable!("ran out of trash entry names")
+    }
+
+    /// List games in the trash, most recently deleted first
+    pub fn list_trash(&self) -> Result<Vec<TrashEntry>> {
+        let mut entries = Vec::new();
+
+        for dir in read_dir_sorted(&self.trash_dir)? {
+            let path = dir.join(TRASH_ENTRY_FILE_NAME);
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            match serde_json::from_str::<TrashEntry>(&content) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::warn!("Ignoring unreadable trash entry {}: {}", path.display(), e),
+            }
+        }
+
+        entries.sort_by_key(|e| std::cmp::Reverse(e.trashed_at));
+
+        Ok(entries)
+    }
+
+    /// Put a trashed game back where it was
+    pub fn restore_from_trash(&self, id: &str) -> Result<LocalGame> {
+        let entry_dir = self.trash_dir.join(id);
+        let content = fs::read_to_string(entry_dir.join(TRASH_ENTRY_FILE_NAME))
+            .map_err(|_| StorageError::TrashEntryNotFound(id.to_string()))?;
+        let entry: TrashEntry = serde_json::from_str(&content)
+            .context("Failed to parse trash entry")?;
+
+        let mut metadata = self.load_metadata()?;
+        if metadata.games.contains_key(&entry.game.tuid) {
+            return Err(StorageError::AlreadyInLibrary(entry.game.title).into());
+        }
+
+        for item in &entry.items {
+            if item.original.exists() {
+                return Err(StorageError::RestoreConflict(item.original.clone()).into());
+            }
+        }
+
+        for item in &entry.items {
+            if let Some(parent) = item.original.parent() {
+                fs::create_dir_all(parent).context("Failed to recreate directory")?;
+            }
+            fs::rename(entry_dir.join(&item.stored), &item.original)
+                .with_context(|| format!("Failed to restore {}", item.original.display()))?;
+        }
+
+        metadata.games.insert(entry.game.tuid.clone(), entry.game.clone());
+        if !entry.saves.is_empty() {
+            metadata.saves.insert(entry.game.tuid.clone(), entry.saves);
+        }
+        self.save_metadata(&metadata)?;
+
+        fs::remove_dir_all(&entry_dir).context("Failed to clean up trash entry")?;
+
+        Ok(entry.game)
+    }
+
+    /// Permanently delete trash entries older than `retention`
+    ///
+    /// Returns the number of bytes freed.
+    pub fn purge_trash(&self, retention: std::time::Duration) -> Result<u64> {
+        let mut freed = 0;
+
+        for entry in self.list_trash()? {
+            let expired = SystemTime::now()
+                .duration_since(entry.trashed_at)
+                .map(|age| age >= retention)
+                .unwrap_or(false);
+
+            if expired {
+                let dir = self.trash_dir.join(&entry.id);
+                freed += dir_size(&dir);
+                fs::remove_dir_all(&dir)
+                    .with_context(|| format!("Failed to purge {}", dir.display()))?;
+            }
+        }
+
+        Ok(freed)
+    }
+
+    /// Directory where session transcripts for a game are kept
+    ///
+    /// Games in their own directory keep transcripts there; games stored with
+    /// the legacy flat layout use their save directory instead.
+    pub fn transcript_dir(&self, game: &LocalGame) -> PathBuf {
+        match self.game_root(&game.file_path) {
+            Some(dir) => dir.join("transcripts"),
+            None => self.get_save_dir(&game.tuid).join("transcripts"),
+        }
+    }
+
+    /// Directory under `games/` that holds everything for a game
+    ///
+    /// Returns `None` for games stored with the legacy flat layout, where the
+    /// game file sits directly in the games directory.
+    fn game_root(&self, file_path: &Path) -> Option<PathBuf> {
+        let relative = file_path.strip_prefix(&self.games_dir).ok()?;
+        let mut components = relative.components();
+        let first = components.next()?;
+
+        // A single component means the file itself is directly in games/
+        components.next()?;
+
+        Some(self.games_dir.join(first))
+    }
+
+    /// Find game directories, covers and saves that no downloaded game references
+    pub fn find_orphans(&self) -> Result<Vec<Orphan>> {
+        let metadata = self.load_metadata()?;
+        let mut orphans = Vec::new();
+
+        // Everything under games/ that belongs to a known game
+        let mut referenced: Vec<PathBuf> = Vec::new();
+        for game in metadata.games.values() {
+            match self.game_root(&game.file_path) {
+                Some(dir) => referenced.push(dir),
+                None => referenced.push(game.file_path.clone()),
             }
+            if let Some(details_path) = &game.details_path {
+                referenced.push(details_path.clone());
+            }
+        }
+
+        for path in read_dir_sorted(&self.games_dir)? {
+            if !referenced.contains(&path) {
+                orphans.push(Orphan::new(OrphanKind::Game, path));
+            }
+        }
+
+        let covers: Vec<&PathBuf> = metadata
+            .games
+            .values()
+            .filter_map(|g| g.cover_art_path.as_ref())
+            .collect();
 
+        for path in read_dir_sorted(&self.base_dir.join("covers"))? {
+            if !covers.contains(&&path) {
+                orphans.push(Orphan::new(OrphanKind::Cover, path));
+            }
+        }
+
+        for path in read_dir_sorted(&self.saves_dir)? {
+            let known = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|tuid| metadata.games.contains_key(tuid))
+                .unwrap_or(false);
+
+            if !known {
+                orphans.push(Orphan::new(OrphanKind::Saves, path));
+            }
+        }
+
+        Ok(orphans)
+    }
+
+    /// Delete orphans found by `find_orphans`, returning the bytes freed
+    pub fn remove_orphans(&self, orphans: &[Orphan]) -> Result<u64> {
+        let mut freed = 0;
+
+        for orphan in orphans {
+            if orphan.path.is_dir() {
+                fs::remove_dir_all(&orphan.path)
+                    .with_context(|| format!("Failed to remove {}", orphan.path.display()))?;
+            } else if orphan.path.exists() {
+                fs::remove_file(&orphan.path)
+                    .with_context(|| format!("Failed to remove {}", orphan.path.display()))?;
+            }
+            freed += orphan.size;
+        }
+
+        // Drop save records for games that are gone
+        let mut metadata = self.load_metadata()?;
+        let before = metadata.saves.len();
+        let games = &metadata.games;
+        metadata.saves.retain(|tuid, _| games.contains_key(tuid));
+        if metadata.saves.len() != before {
             self.save_metadata(&metadata)?;
         }
 
-        Ok(())
+        Ok(freed)
     }
 
-    /// Record that a game was played
-    pub fn record_game_played(&self, tuid: &str) -> Result<()> {
+    /// Record a finished play session
+    ///
+    /// Save files in the game and save directories modified since the session
+    /// started are attached to it.
+    pub fn record_play_session(&self, tuid: &str, mut session: PlaySession) -> Result<()> {
         let mut metadata = self.load_metadata()?;
 
+        let Some(game) = metadata.games.get(tuid) else {
+            return Ok(());
+        };
+
+        let mut dirs = vec![self.get_save_dir(tuid)];
+        dirs.extend(self.game_root(&game.file_path));
+
+        for dir in dirs {
+            for path in read_dir_sorted(&dir)? {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if is_save_file(&path) && modified.map(|m| m >= session.started_at).unwrap_or(false) {
+                    session.saves_created.push(path);
+                }
+            }
+        }
+
         if let Some(game) = metadata.games.get_mut(tuid) {
             game.play_count += 1;
-            game.last_played = Some(SystemTime::now());
-            self.save_metadata(&metadata)?;
+            game.last_played = Some(session.ended_at);
+            game.sessions.push(session);
         }
 
-        Ok(())
+        self.save_metadata(&metadata)
     }
 
     /// Get all save files for a game
@@ -520,7 +1046,7 @@ impl GameStorage {
         }
 
         // Sort by save date (newest first)
-        saves.sort_by(|a, b| b.save_date.cmp(&a.save_date));
+        saves.sort_by_key(|s| std::cmp::Reverse(s.save_date));
 
         Ok(saves)
     }
@@ -553,22 +1079,172 @@ pub struct StorageStats {
 impl StorageStats {
     #[allow(dead_code)]
     pub fn format_size(&self) -> String {
-        const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-        let mut size = self.total_size as f64;
-        let mut unit_index = 0;
+        format_size(self.total_size)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanKind {
+    Game,
+    Cover,
+    Saves,
+}
 
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
+impl fmt::Display for OrphanKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrphanKind::Game => write!(f, "game"),
+            OrphanKind::Cover => write!(f, "cover"),
+            OrphanKind::Saves => write!(f, "saves"),
         }
+    }
+}
 
-        format!("{:.1} {}", size, UNITS[unit_index])
+/// A file or directory in storage that no downloaded game references
+#[derive(Debug, Clone)]
+pub struct Orphan {
+    pub kind: OrphanKind,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl Orphan {
+    fn new(kind: OrphanKind, path: PathBuf) -> Self {
+        let size = dir_size(&path);
+        Orphan { kind, path, size }
     }
 }
 
+/// Name of the record kept in each trash entry directory
+const TRASH_ENTRY_FILE_NAME: &str = "entry.json";
+
+/// A deleted game waiting in `~/.glkcli/trash`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Name of the entry's directory under the trash directory
+    pub id: String,
+    pub trashed_at: SystemTime,
+    pub game: LocalGame,
+    pub saves: Vec<SaveFile>,
+    pub items: Vec<TrashedItem>,
+}
+
+/// A file or directory moved into a trash entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItem {
+    /// Where it lived before it was trashed
+    pub original: PathBuf,
+    /// Its name inside the trash entry directory
+    pub stored: String,
+}
+
+/// The release recorded in a story file's header, if it has one
+fn story_release(path: &Path) -> Option<String> {
+    inspect::inspect(path).ok().and_then(|info| info.release)
+}
+
+/// Make a TUID safe to use in a directory name
+fn sanitize_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// File extensions interpreters commonly use for save files
+const SAVE_EXTENSIONS: &[&str] = &["sav", "save", "qzl", "qut", "glksave"];
+
+fn is_save_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SAVE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Total size of a file, or of all files below a directory
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Undo a partly completed `trash_game`, putting moved items back
+///
+/// Errors are only logged: the caller is already reporting the failure that
+/// made the rollback necessary.
+fn roll_back_trash(entry_dir: &Path, items: &[TrashedItem]) {
+    for item in items.iter().rev() {
+        if let Err(e) = fs::rename(entry_dir.join(&item.stored), &item.original) {
+            log::error!("Failed to restore {} from trash: {}", item.original.display(), e);
+        }
+    }
+
+    if let Err(e) = fs::remove_dir_all(entry_dir) {
+        log::warn!("Failed to remove {}: {}", entry_dir.display(), e);
+    }
+}
+
+/// Entries of a directory in name order, or nothing if it doesn't exist
+fn read_dir_sorted(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+    paths.sort();
+
+    Ok(paths)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
+
+    fn test_game() -> Game {
+        serde_json::from_str(r#"{
+            "tuid": "0dbnusxunq7fw5ro",
+            "title": "Zork I",
+            "link": "https://ifdb.org/viewgame?id=0dbnusxunq7fw5ro",
+            "author": "Infocom"
+        }"#).unwrap()
+    }
+
+    fn test_details() -> GameDetails {
+        serde_json::from_str(r#"{
+            "identification": { "ifids": ["ZCODE-88-840726"], "format": "zcode" },
+            "bibliographic": {
+                "title": "Zork I",
+                "author": "Infocom",
+                "language": "en",
+                "firstpublished": "1980",
+                "genre": "Fantasy",
+                "description": "The Great Underground Empire."
+            },
+            "contacts": { "url": "https://example.com" },
+            "ifdb": {
+                "tuid": "0dbnusxunq7fw5ro",
+                "link": "https://ifdb.org/viewgame?id=0dbnusxunq7fw5ro",
+                "playTimeInMinutes": 300,
+                "starRating": 4.0,
+                "ratingCountTot": 120,
+                "tags": [{ "name": "treasure hunt" }],
+                "downloads": { "links": [{ "url": "https://example.com/zork1.z3", "title": "Story file", "isGame": true, "format": "zcode" }] }
+            }
+        }"#).unwrap()
+    }
 
     #[test]
     fn test_sanitize_filename() {
@@ -585,4 +1261,292 @@ mod tests {
             "A_B_C_D_E_F_G_H_I_J"
         );
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_details_snapshot_saved_with_game() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+        let details = test_details();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), Some(&details), b"\x03story data", "z3")
+            .await
+            .unwrap();
+
+        let snapshot_path = local.details_path.clone().expect("snapshot should be recorded");
+        assert_eq!(snapshot_path.parent(), local.file_path.parent());
+
+        let loaded = storage.load_game_details(&local).unwrap().expect("snapshot should load");
+        let biblio = loaded.bibliographic.unwrap();
+        assert_eq!(biblio.genre.as_deref(), Some("Fantasy"));
+        assert_eq!(biblio.firstpublished.as_deref(), Some("1980"));
+        let ifdb = loaded.ifdb.unwrap();
+        assert_eq!(ifdb.play_time_in_minutes, Some(300));
+        assert_eq!(ifdb.tags.unwrap()[0].name, "treasure hunt");
+        assert_eq!(ifdb.downloads.unwrap().links[0].format.as_deref(), Some("zcode"));
+        assert_eq!(
+            loaded.identification.unwrap().ifids.unwrap(),
+            vec!["ZCODE-88-840726".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_game_details_backfills_snapshot() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+            .await
+            .unwrap();
+        assert!(local.details_path.is_none());
+        assert!(storage.load_game_details(&local).unwrap().is_none());
+
+        storage.update_game_details(&local.tuid, &test_details()).unwrap();
+
+        let updated = storage.get_game(&local.tuid).unwrap().unwrap();
+        assert!(updated.details_path.is_some());
+        assert!(storage.load_game_details(&updated).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_release_read_from_story_file() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let mut story = crate::detect::test_story(3);
+        story[0x02..0x04].copy_from_slice(&88u16.to_be_bytes());
+        story[0x12..0x18].copy_from_slice(b"840726");
+
+        let local = storage.add_game_with_cover(&test_game(), None, &story, "z3").await.unwrap();
+        assert_eq!(local.release.as_deref(), Some("Release 88 / 840726"));
+        assert_eq!(local.sha256, Some(integrity::sha256_file(&local.file_path).unwrap()));
+
+        // Metadata written before releases were recorded
+        let forget_release = || {
+            let mut metadata = storage.load_metadata().unwrap();
+            let game = metadata.games.get_mut(&local.tuid).unwrap();
+            game.release = None;
+            game.release_checked = false;
+            storage.save_metadata(&metadata).unwrap();
+        };
+        forget_release();
+
+        assert_eq!(storage.fill_release_info().unwrap(), 1);
+        let updated = storage.get_game(&local.tuid).unwrap().unwrap();
+        assert_eq!(updated.release, local.release);
+        assert!(updated.release_checked);
+        assert_eq!(storage.fill_release_info().unwrap(), 0);
+
+        // A file without a release is only read once
+        forget_release();
+        fs::write(&local.file_path, b"").unwrap();
+        assert_eq!(storage.fill_release_info().unwrap(), 0);
+        assert!(storage.get_game(&local.tuid).unwrap().unwrap().release_checked);
+
+        fs::write(&local.file_path, &story).unwrap();
+        assert_eq!(storage.fill_release_info().unwrap(), 0);
+        assert!(storage.get_game(&local.tuid).unwrap().unwrap().release.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_purged_game_leaves_nothing_behind() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), Some(&test_details()), b"\x03story data", "z3")
+            .await
+            .unwrap();
+        let game_dir = local.file_path.parent().unwrap().to_path_buf();
+        fs::write(game_dir.join("extra.txt"), b"from the zip").unwrap();
+        fs::write(game_dir.join("game.sav"), b"save").unwrap();
+
+        storage.trash_game(&local.tuid).unwrap();
+        let freed = storage.purge_trash(std::time::Duration::ZERO).unwrap();
+
+        assert!(!game_dir.exists());
+        assert!(freed > 0);
+        assert!(storage.get_game(&local.tuid).unwrap().is_none());
+        assert!(storage.find_orphans().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_and_remove_orphans() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+            .await
+            .unwrap();
+
+        let stray_dir = temp.path().join("games").join("Stray_12345678");
+        fs::create_dir_all(&stray_dir).unwrap();
+        fs::write(stray_dir.join("stray.z5"), b"12345").unwrap();
+        fs::create_dir_all(temp.path().join("covers")).unwrap();
+        fs::write(temp.path().join("covers").join("gone_cover.png"), b"png").unwrap();
+        fs::create_dir_all(storage.get_save_dir("gone")).unwrap();
+        fs::create_dir_all(storage.get_save_dir(&local.tuid)).unwrap();
+
+        let orphans = storage.find_orphans().unwrap();
+        let kinds: Vec<OrphanKind> = orphans.iter().map(|o| o.kind).collect();
+        assert_eq!(kinds, vec![OrphanKind::Game, OrphanKind::Cover, OrphanKind::Saves]);
+        assert_eq!(orphans[0].path, stray_dir);
+        assert_eq!(orphans[0].size, 5);
+
+        let freed = storage.remove_orphans(&orphans).unwrap();
+        assert_eq!(freed, 8);
+        assert!(!stray_dir.exists());
+        assert!(local.file_path.exists());
+        assert!(storage.get_save_dir(&local.tuid).exists());
+        assert!(storage.find_orphans().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trash_and_restore_game() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), Some(&test_details()), b"\x03story data", "z3")
+            .await
+            .unwrap();
+        fs::create_dir_all(storage.get_save_dir(&local.tuid)).unwrap();
+        fs::write(storage.get_save_dir(&local.tuid).join("turn10.sav"), b"save").unwrap();
+
+        let entry = storage.trash_game(&local.tuid).unwrap();
+        assert!(!local.file_path.exists());
+        assert!(!storage.get_save_dir(&local.tuid).exists());
+        assert!(storage.get_game(&local.tuid).unwrap().is_none());
+        assert!(storage.find_orphans().unwrap().is_empty());
+        assert_eq!(storage.list_trash().unwrap().len(), 1);
+
+        let restored = storage.restore_from_trash(&entry.id).unwrap();
+        assert_eq!(restored.tuid, local.tuid);
+        assert!(local.file_path.exists());
+        assert!(local.details_path.unwrap().exists());
+        assert!(storage.get_save_dir(&local.tuid).join("turn10.sav").exists());
+        assert!(storage.get_game(&local.tuid).unwrap().is_some());
+        assert!(storage.list_trash().unwrap().is_empty());
+
+        let err = storage.restore_from_trash(&entry.id).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StorageError>(),
+            Some(StorageError::TrashEntryNotFound(_))
+        ));
+        let err = storage.trash_game("no-such-game").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StorageError>(),
+            Some(StorageError::GameNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_trash_entries_never_collide() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let local = storage
+                .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+                .await
+                .unwrap();
+            ids.push(storage.trash_game(&local.tuid).unwrap().id);
+        }
+
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(storage.list_trash().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_trash_is_rolled_back() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+            .await
+            .unwrap();
+
+        // A directory can't be moved inside itself, so moving this "cover" fails
+        // after the game directory has already gone to the trash
+        let mut metadata = storage.load_metadata().unwrap();
+        metadata.games.get_mut(&local.tuid).unwrap().cover_art_path = Some(storage.trash_dir.clone());
+        storage.save_metadata(&metadata).unwrap();
+
+        assert!(storage.trash_game(&local.tuid).is_err());
+        assert!(local.file_path.exists());
+        assert!(storage.get_game(&local.tuid).unwrap().is_some());
+        assert!(storage.list_trash().unwrap().is_empty());
+        assert_eq!(fs::read_dir(&storage.trash_dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cover_art_fetched_from_configured_ifdb() {
+        let temp = TempDir::new().unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let cover = server
+            .mock("GET", "/coverart")
+            .match_query(mockito::Matcher::UrlEncoded("id".into(), "0dbnusxunq7fw5ro".into()))
+            .with_header("content-type", "image/png")
+            .with_body(b"png data")
+            .create_async()
+            .await;
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf())
+            .unwrap()
+            .with_ifdb_url(&server.url());
+
+        let mut game = test_game();
+        game.has_cover_art = Some(true);
+        let local = storage.add_game_with_cover(&game, None, b"\x03story data", "z3").await.unwrap();
+
+        cover.assert_async().await;
+        assert_eq!(fs::read(local.cover_art_path.unwrap()).unwrap(), b"png data");
+    }
+
+    #[tokio::test]
+    async fn test_purge_trash_respects_retention() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+            .await
+            .unwrap();
+        storage.trash_game(&local.tuid).unwrap();
+
+        storage.purge_trash(std::time::Duration::from_secs(3600)).unwrap();
+        assert_eq!(storage.list_trash().unwrap().len(), 1);
+
+        let freed = storage.purge_trash(std::time::Duration::ZERO).unwrap();
+        assert!(freed > 0);
+        assert!(storage.list_trash().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_record_play_session() {
+        let temp = TempDir::new().unwrap();
+        let storage = GameStorage::with_base_dir(temp.path().to_path_buf()).unwrap();
+
+        let local = storage
+            .add_game_with_cover(&test_game(), None, b"\x03story data", "z3")
+            .await
+            .unwrap();
+        let started = SystemTime::now() - Duration::from_secs(600);
+        fs::write(local.file_path.parent().unwrap().join("west.qzl"), b"save").unwrap();
+
+        let session = PlaySession::new("bocfel", started, started + Duration::from_secs(600), Some(0));
+        storage.record_play_session(&local.tuid, session).unwrap();
+
+        let game = storage.get_game(&local.tuid).unwrap().unwrap();
+        assert_eq!(game.play_count, 1);
+        assert_eq!(game.sessions.len(), 1);
+        assert_eq!(game.total_play_time(), Duration::from_secs(600));
+        let last = game.last_session().unwrap();
+        assert_eq!(last.interpreter, "bocfel");
+        assert_eq!(last.saves_created.len(), 1);
+        assert!(last.saves_created[0].ends_with("west.qzl"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Supervision of running interpreters
+//!
+//! While an interpreter runs, glkcli catches SIGINT, SIGTERM and SIGHUP and
+//! forwards them to the interpreter instead of dying and leaving it orphaned
+//! with the terminal in an unknown state. An interpreter sharing glkcli's
+//! terminal is in its foreground process group and already gets the signals
+//! the terminal sends (Ctrl-C), so only SIGTERM and SIGHUP, which are usually
+//! sent to glkcli alone, are forwarded to it. An optional wall-clock limit stops
+//! interpreters that run too long: first politely with SIGTERM, then with
+//! SIGKILL if they do not exit.
+
+use anyhow::{Context, Result};
+use signal_hook::consts::{SIGHUP, SIGINT, SIGKILL, SIGTERM};
+use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Signals caught, and passed on to an interpreter in its own session
+const FORWARDED_SIGNALS: [i32; 3] = [SIGINT, SIGTERM, SIGHUP];
+
+/// Signals passed on to an interpreter in glkcli's own process group
+const SHARED_TERMINAL_SIGNALS: [i32; 2] = [SIGTERM, SIGHUP];
+
+/// How long an interpreter gets to exit after SIGTERM before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// How often `wait` checks on the child
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Signal handlers shared by every supervisor
+///
+/// Handlers are installed once and never removed: signal-hook cannot restore
+/// the default action when a handler is unregistered, so instead the default
+/// action is re-enabled whenever neither an interpreter nor the TUI is being
+/// looked after.
+struct SignalState {
+    /// Last caught signal, or 0
+    pending: Arc<AtomicUsize>,
+    /// Whether caught signals should have their default effect on glkcli
+    unsupervised: Arc<AtomicBool>,
+}
+
+static SIGNALS: OnceLock<std::result::Result<SignalState, String>> = OnceLock::new();
+
+fn signal_state() -> Result<&'static SignalState> {
+    let state = SIGNALS.get_or_init(|| {
+        let state = SignalState {
+            pending: Arc::new(AtomicUsize::new(0)),
+            unsupervised: Arc::new(AtomicBool::new(true)),
+        };
+
+        for signal in FORWARDED_SIGNALS {
+            signal_hook::flag::register_usize(signal, Arc::clone(&state.pending), signal as usize)
+                .map_err(|e| e.to_string())?;
+            signal_hook::flag::register_conditional_default(signal, Arc::clone(&state.unsupervised))
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(state)
+    });
+
+    state
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("Failed to install signal handler: {}", e))
+}
+
+/// Catches SIGINT, SIGTERM and SIGHUP while the TUI owns the terminal
+///
+/// Dying on one of these would leave the terminal in raw mode on the
+/// alternate screen, so the signal is only noted and the TUI quits through
+/// its normal restore path.
+pub struct TerminationWatch {
+    signals: &'static SignalState,
+    was_unsupervised: bool,
+}
+
+impl TerminationWatch {
+    pub fn new() -> Result<Self> {
+        let signals = signal_state()?;
+        signals.pending.store(0, Ordering::SeqCst);
+        let was_unsupervised = signals.unsupervised.swap(false, Ordering::SeqCst);
+
+        Ok(TerminationWatch { signals, was_unsupervised })
+    }
+
+    /// The signal caught since the last call, if any
+    pub fn take(&self) -> Option<i32> {
+        match self.signals.pending.swap(0, Ordering::SeqCst) as i32 {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+}
+
+impl Drop for TerminationWatch {
+    fn drop(&mut self) {
+        self.signals.unsupervised.store(self.was_unsupervised, Ordering::SeqCst);
+    }
+}
+
+/// Watches over one interpreter process
+pub struct Supervisor {
+    signals: &'static SignalState,
+    /// Whether caught signals had their default effect before supervising
+    was_unsupervised: bool,
+    forwarded: &'static [i32],
+    deadline: Option<Instant>,
+    kill_at: Option<Instant>,
+    /// Last signal glkcli received
+    pub received: Option<i32>,
+    /// Whether the time limit was reached
+    pub timed_out: bool,
+}
+
+impl Supervisor {
+    /// Supervise a child running in its own session, such as under a PTY
+    ///
+    /// The time limit, if any, is counted from now.
+    pub fn new(time_limit: Option<Duration>) -> Result<Self> {
+        Self::with_forwarded(time_limit, &FORWARDED_SIGNALS)
+    }
+
+    /// Supervise a child sharing glkcli's terminal and process group
+    pub fn sharing_terminal(time_limit: Option<Duration>) -> Result<Self> {
+        Self::with_forwarded(time_limit, &SHARED_TERMINAL_SIGNALS)
+    }
+
+    fn with_forwarded(time_limit: Option<Duration>, forwarded: &'static [i32]) -> Result<Self> {
+        let signals = signal_state()?;
+        signals.pending.store(0, Ordering::SeqCst);
+        let was_unsupervised = signals.unsupervised.swap(false, Ordering::SeqCst);
+
+        Ok(Supervisor {
+            signals,
+            was_unsupervised,
+            forwarded,
+            deadline: time_limit.map(|limit| Instant::now() + limit),
+            kill_at: None,
+            received: None,
+            timed_out: false,
+        })
+    }
+
+    /// Forward any caught signal and enforce the time limit
+    ///
+    /// Call this regularly while the child runs.
+    pub fn check(&mut self, child: &Child) {
+        let signal = self.signals.pending.swap(0, Ordering::SeqCst) as i32;
+        if signal != 0 {
+            self.received = Some(signal);
+            if self.forwarded.contains(&signal) {
+                log::info!("Forwarding signal {} to interpreter", signal);
+                send_signal(child, signal);
+            }
+        }
+
+        let now = Instant::now();
+        if !self.timed_out && self.deadline.map(|d| now >= d).unwrap_or(false) {
+            log::info!("Interpreter reached the time limit, stopping it");
+            self.timed_out = true;
+            self.kill_at = Some(now + KILL_GRACE);
+            send_signal(child, SIGTERM);
+        }
+
+        if self.kill_at.map(|k| now >= k).unwrap_or(false) {
+            self.kill_at = None;
+            send_signal(child, SIGKILL);
+        }
+    }
+
+    /// Wait for the child to exit, supervising it meanwhile
+    pub fn wait(&mut self, child: &mut Child) -> Result<ExitStatus> {
+        loop {
+            if let Some(status) = child.try_wait().context("Failed to wait for interpreter")? {
+                return Ok(status);
+            }
+            self.check(child);
+            std::thread::sleep(WAIT_INTERVAL);
+        }
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        // The TUI keeps catching signals once the game is over
+        self.signals.unsupervised.store(self.was_unsupervised, Ordering::SeqCst);
+    }
+}
+
+fn send_signal(child: &Child, signal: i32) {
+    // SAFETY: kill has no memory safety requirements
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, signal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::process::Command;
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_time_limit_stops_child() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let mut supervisor = Supervisor::new(Some(Duration::from_millis(200))).unwrap();
+
+        let started = Instant::now();
+        let status = supervisor.wait(&mut child).unwrap();
+
+        assert!(supervisor.timed_out);
+        assert!(!status.success());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_caught_signal_is_forwarded() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let mut supervisor = Supervisor::new(None).unwrap();
+
+        signal_hook::low_level::raise(SIGTERM).unwrap();
+        let status = supervisor.wait(&mut child).unwrap();
+
+        assert_eq!(supervisor.received, Some(SIGTERM));
+        assert!(!status.success());
+    }
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_termination_watch_outlives_supervisor() {
+        let watch = TerminationWatch::new().unwrap();
+        assert_eq!(watch.take(), None);
+
+        let mut child = Command::new("true").spawn().unwrap();
+        Supervisor::new(None).unwrap().wait(&mut child).unwrap();
+
+        // Still caught after the game, rather than killing glkcli
+        signal_hook::low_level::raise(SIGTERM).unwrap();
+        assert_eq!(watch.take(), Some(SIGTERM));
+        assert_eq!(watch.take(), None);
+    }
+
+    #[test]
+    #[serial(supervisor)]
+    fn test_child_sharing_terminal_gets_one_sigint() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let count = temp.path().join("count");
+
+        // Counts SIGINTs; the child stays in this process's group
+        let script = r#"n=0; trap 'n=$((n+1)); echo $n > "$1"' INT; echo 0 > "$1"
+            i=0; while [ $i -lt 10 ]; do sleep 0.1; i=$((i+1)); done"#;
+        let mut child = Command::new("sh").args(["-c", script, "sh"]).arg(&count).spawn().unwrap();
+        let mut supervisor = Supervisor::sharing_terminal(None).unwrap();
+
+        let read_count = || std::fs::read_to_string(&count).unwrap_or_default().trim().to_string();
+        let wait_for = |expected: &str| {
+            let started = Instant::now();
+            while read_count() != expected && started.elapsed() < Duration::from_secs(5) {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        };
+
+        // Ctrl-C: the terminal signals every process in the foreground group
+        wait_for("0");
+        // SAFETY: kill has no memory safety requirements
+        unsafe {
+            libc::kill(child.id() as libc::pid_t, SIGINT);
+        }
+        wait_for("1");
+        signal_hook::low_level::raise(SIGINT).unwrap();
+
+        let status = supervisor.wait(&mut child).unwrap();
+
+        assert!(status.success());
+        assert_eq!(supervisor.received, Some(SIGINT));
+        assert_eq!(read_count(), "1");
+    }
+}
@@ -0,0 +1,128 @@
+//! Single-line text entry with a cursor
+//!
+//! Text is edited a character at a time, never a byte, so accented letters,
+//! CJK titles and emoji can be typed, moved over and deleted like ASCII. The
+//! cursor's on-screen column accounts for double-width characters.
+
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    text: String,
+    /// Cursor position, in characters from the start
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Insert a character at the cursor and move past it
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_index(self.cursor);
+        self.text.insert(at, c);
+        self.cursor += 1;
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let at = self.byte_index(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    /// Delete the character under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            let at = self.byte_index(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    /// Terminal columns between the start of the text and the cursor
+    pub fn cursor_column(&self) -> u16 {
+        let before = &self.text[..self.byte_index(self.cursor)];
+        before.width().try_into().unwrap_or(u16::MAX)
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        text.chars().for_each(|c| input.insert(c));
+        input
+    }
+
+    #[test]
+    fn test_editing_multibyte_text() {
+        let mut input = typed("Cafe");
+        input.backspace();
+        input.insert('é');
+        assert_eq!(input.as_str(), "Café");
+
+        input.move_home();
+        input.move_right();
+        input.delete();
+        input.insert('h');
+        assert_eq!(input.as_str(), "Chfé");
+
+        input.move_end();
+        input.backspace();
+        input.backspace();
+        assert_eq!(input.as_str(), "Ch");
+    }
+
+    #[test]
+    fn test_cursor_column_counts_wide_characters() {
+        let mut input = typed("ゼルダ");
+        assert_eq!(input.cursor_column(), 6);
+        input.move_left();
+        assert_eq!(input.cursor_column(), 4);
+
+        input.clear();
+        assert_eq!(input.cursor_column(), 0);
+        input.move_left();
+        input.backspace();
+        input.delete();
+        assert_eq!(input.as_str(), "");
+    }
+}
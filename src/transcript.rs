@@ -0,0 +1,260 @@
+//! Play session transcripts
+//!
+//! A transcript is a pair of files named after the time the session started:
+//! `<stamp>.out` holds the raw interpreter output, escape sequences and all,
+//! and `<stamp>.in` holds the raw player input. Transcripts live in a
+//! `transcripts` directory in the game's folder.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Extension of the raw interpreter output file
+const OUTPUT_EXTENSION: &str = "out";
+
+/// Extension of the raw player input file
+const INPUT_EXTENSION: &str = "in";
+
+/// A recorded session on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub output: PathBuf,
+    pub input: PathBuf,
+}
+
+impl Transcript {
+    /// Name shown for the transcript, the session's start time
+    pub fn name(&self) -> String {
+        self.output
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("transcript")
+            .to_string()
+    }
+
+    /// The interpreter output with terminal escape sequences removed
+    pub fn to_plain_text(&self) -> Result<String> {
+        let raw = fs::read(&self.output)
+            .with_context(|| format!("Failed to read transcript: {}", self.output.display()))?;
+        Ok(strip_ansi(&raw))
+    }
+
+    /// Write the plain text version next to the raw files
+    pub fn export_text(&self) -> Result<PathBuf> {
+        let path = self.output.with_extension("txt");
+        fs::write(&path, self.to_plain_text()?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Records a session as it is relayed
+pub struct TranscriptWriter {
+    output: File,
+    input: File,
+    transcript: Transcript,
+}
+
+impl TranscriptWriter {
+    /// Start a new transcript in `dir`, named after the current time
+    pub fn create(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).context("Failed to create transcript directory")?;
+
+        let stamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+        let mut name = stamp.clone();
+        let mut n = 1;
+        while dir.join(&name).with_extension(OUTPUT_EXTENSION).exists() {
+            n += 1;
+            name = format!("{}_{}", stamp, n);
+        }
+
+        let transcript = Transcript {
+            output: dir.join(&name).with_extension(OUTPUT_EXTENSION),
+            input: dir.join(&name).with_extension(INPUT_EXTENSION),
+        };
+
+        Ok(TranscriptWriter {
+            output: File::create(&transcript.output).context("Failed to create transcript")?,
+            input: File::create(&transcript.input).context("Failed to create transcript")?,
+            transcript,
+        })
+    }
+
+    pub fn record_output(&mut self, data: &[u8]) -> Result<()> {
+        self.output.write_all(data).context("Failed to write transcript")
+    }
+
+    pub fn record_input(&mut self, data: &[u8]) -> Result<()> {
+        self.input.write_all(data).context("Failed to write transcript")
+    }
+
+    /// Flush both files and return where they were written
+    pub fn finish(mut self) -> Result<Transcript> {
+        self.output.flush().context("Failed to write transcript")?;
+        self.input.flush().context("Failed to write transcript")?;
+        Ok(self.transcript)
+    }
+}
+
+/// List the transcripts in a directory, newest first
+pub fn list(dir: &Path) -> Result<Vec<Transcript>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut transcripts = Vec::new();
+    for entry in fs::read_dir(dir).context("Failed to read transcript directory")? {
+        let path = entry.context("Failed to read directory entry")?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some(OUTPUT_EXTENSION) {
+            transcripts.push(Transcript {
+                input: path.with_extension(INPUT_EXTENSION),
+                output: path,
+            });
+        }
+    }
+
+    // Names are timestamps, so reverse name order is newest first
+    transcripts.sort_by(|a, b| b.output.cmp(&a.output));
+
+    Ok(transcripts)
+}
+
+/// Convert raw terminal output to plain text
+///
+/// Escape sequences are dropped, backspaces applied, CRLF line endings
+/// normalised and cursor positioning turned into line breaks so full-screen
+/// interpreters still produce readable text.
+pub fn strip_ansi(raw: &[u8]) -> String {
+    let mut out: Vec<u8> = Vec::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < raw.len() {
+        match raw[i] {
+            0x1b => {
+                i += 1;
+                match raw.get(i) {
+                    // CSI: parameters then a final byte in 0x40..=0x7e
+                    Some(b'[') => {
+                        i += 1;
+                        while i < raw.len() && !(0x40..=0x7e).contains(&raw[i]) {
+                            i += 1;
+                        }
+                        if matches!(raw.get(i), Some(b'H' | b'f' | b'd')) {
+                            new_line(&mut out);
+                        }
+                        i += 1;
+                    }
+                    // OSC and other string sequences end with BEL or ESC '\'
+                    Some(b']' | b'P' | b'X' | b'^' | b'_') => {
+                        i += 1;
+                        while i < raw.len() {
+                            if raw[i] == 0x07 {
+                                i += 1;
+                                break;
+                            }
+                            if raw[i] == 0x1b && raw.get(i + 1) == Some(&b'\\') {
+                                i += 2;
+                                break;
+                            }
+                            i += 1;
+                        }
+                    }
+                    // Character set designation takes one more byte
+                    Some(b'(' | b')' | b'*' | b'+') => i += 2,
+                    Some(_) => i += 1,
+                    None => {}
+                }
+            }
+            0x08 => {
+                // Remove the last character, including all its UTF-8 bytes
+                while let Some(byte) = out.pop() {
+                    if byte & 0xc0 != 0x80 {
+                        break;
+                    }
+                }
+                i += 1;
+            }
+            b'\r' => {
+                if raw.get(i + 1) != Some(&b'\n') {
+                    new_line(&mut out);
+                }
+                i += 1;
+            }
+            b'\n' | b'\t' => {
+                out.push(raw[i]);
+                i += 1;
+            }
+            byte if byte < 0x20 || byte == 0x7f => i += 1,
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    let text = String::from_utf8_lossy(&out);
+
+    // Trim trailing spaces and collapse runs of blank lines
+    let mut result = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Start a new line unless the output is already at the start of one
+fn new_line(out: &mut Vec<u8>) {
+    if !out.is_empty() && out.last() != Some(&b'\n') {
+        out.push(b'\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_ansi_sequences() {
+        let raw = b"\x1b[1;1H\x1b[7mWest of House\x1b[0m\r\n\x1b]0;title\x07You are standing\x1b(B in a field.\r\n";
+        assert_eq!(strip_ansi(raw), "West of House\nYou are standing in a field.\n");
+    }
+
+    #[test]
+    fn test_strip_ansi_backspace_and_blank_lines() {
+        assert_eq!(strip_ansi(b">lookk\x08\r\n\n\n\nOk."), ">look\n\nOk.\n");
+        assert_eq!(strip_ansi("caf\u{e9}\x08e".as_bytes()), "cafe\n");
+    }
+
+    #[test]
+    fn test_writer_and_list() {
+        let temp = TempDir::new().unwrap();
+
+        let mut first = TranscriptWriter::create(temp.path()).unwrap();
+        first.record_output(b"\x1b[1mZORK I\x1b[0m\r\n").unwrap();
+        first.record_input(b"north\r").unwrap();
+        let first = first.finish().unwrap();
+        let second = TranscriptWriter::create(temp.path()).unwrap().finish().unwrap();
+
+        let listed = list(temp.path()).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.contains(&first) && listed.contains(&second));
+        assert_eq!(fs::read(&first.input).unwrap(), b"north\r");
+
+        let exported = first.export_text().unwrap();
+        assert_eq!(fs::read_to_string(exported).unwrap(), "ZORK I\n");
+    }
+}
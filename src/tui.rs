@@ -10,6 +10,7 @@
 //! - `storage` - Local game storage and management
 //! - `launcher` - Game interpreter detection and launching
 //! - `network` - Network connectivity checking
+//! - `cover_art` - Cover art rendering for the details views
 //!
 //! ## Architecture
 //!
@@ -37,13 +38,24 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
+    widgets::ListState,
     Terminal,
 };
-use std::io;
+use std::io::{self, Write};
 
-use crate::app::state::{TuiApp, AppState, InputMode};
-use crate::ifdb::{Game, SearchOptions};
-use crate::storage::{LocalGame, SaveFile};
+use crate::app::state::{TuiApp, AppState, InputMode, LaunchOptionsForm, PendingAction, Playability, ResultBadges, SearchFiltersForm};
+use crate::cache::{self, ResponseCache};
+use crate::config::GameFormat;
+use crate::cover_art::{self, CoverImage, GraphicsProtocol};
+use crate::error::LaunchError;
+use crate::ifdb::{Fetched, Game, GameDetails, SearchOptions};
+use crate::inspect;
+use crate::integrity;
+use crate::storage::{LocalGame, PlaySession, SaveFile};
+use crate::supervisor::TerminationWatch;
+use crate::transcript;
+use crate::utils::format_duration;
+use crate::web;
 
 /// Run the TUI application
 ///
@@ -58,6 +70,9 @@ use crate::storage::{LocalGame, SaveFile};
 /// * `debug` - Enable debug logging to ~/.glkcli/debug.log
 /// * `assume_online` - Assume network is available (skip connectivity check)
 pub async fn run_tui(debug: bool, assume_online: bool) -> Result<()> {
+    // SIGTERM or SIGHUP must not kill glkcli with the terminal in raw mode
+    let signals = TerminationWatch::new()?;
+
     // Setup terminal
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = io::stdout();
@@ -66,23 +81,16 @@ pub async fn run_tui(debug: bool, assume_online: bool) -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
-    // Create app
-    let mut app = TuiApp::new(debug, assume_online).await?;
-    
-    // Load initial data
-    app.refresh_downloaded_games().await?;
-    
-    // Only browse games if online
-    if app.is_online {
-        app.browse_popular_games().await?;
-    } else {
-        // Start on the downloaded games tab if offline
-        app.current_tab = 1;
-        app.set_status_message("Offline - showing downloaded games only".to_string());
-    }
-    
-    // Run app
-    let result = app.run(&mut terminal).await;
+    // Make sure a panic doesn't leave the terminal in raw mode
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+        default_hook(info);
+    }));
+
+    // Restore the terminal whether or not the app started successfully
+    let result = run_app(&mut terminal, &signals, debug, assume_online).await;
 
     // Restore terminal
     disable_raw_mode().context("Failed to disable raw mode")?;
@@ -99,16 +107,80 @@ pub async fn run_tui(debug: bool, assume_online: bool) -> Result<()> {
     result
 }
 
+/// Create the app, load its initial data and run the event loop
+async fn run_app<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    signals: &TerminationWatch,
+    debug: bool,
+    assume_online: bool,
+) -> Result<()> {
+    // Create app
+    let mut app = TuiApp::new(debug, assume_online).await?;
+    
+    // Deleted games only stay restorable for a while
+    if let Err(e) = app.storage.purge_trash(app.settings.trash_retention()) {
+        log::warn!("Failed to purge trash: {}", e);
+    }
+
+    // Listings pile up with every distinct search
+    if let Err(e) = ResponseCache::new().and_then(|cache| cache.prune(cache::MAX_AGE)) {
+        log::warn!("Failed to prune IFDB cache: {}", e);
+    }
+
+    // Games downloaded before releases were recorded
+    if let Err(e) = app.storage.fill_release_info() {
+        log::warn!("Failed to read story file releases: {}", e);
+    }
+
+    // Load initial data
+    app.refresh_downloaded_games().await?;
+    
+    // When offline the browse list comes from the cache, if there is one
+    app.browse_popular_games().await?;
+    if !app.is_online {
+        // Start on the downloaded games tab if offline
+        app.current_tab = 1;
+        app.set_status_message("Offline - showing downloaded games and cached IFDB data".to_string());
+    }
+    
+    // Run app
+    app.run(terminal, signals).await
+}
+
+/// Status message for a newly added game, warning if its story looks damaged
+fn added_game_message(game: &LocalGame, message: String) -> String {
+    match integrity::check_story(&game.file_path) {
+        Ok(check) if check.is_damaged() => {
+            format!("{} - but the story file looks damaged ({})", message, check)
+        }
+        _ => message,
+    }
+}
+
 impl TuiApp {
-    async fn run<B: ratatui::backend::Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+    async fn run<B: ratatui::backend::Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        signals: &TerminationWatch,
+    ) -> Result<()> {
         // Clear terminal on startup to ensure clean initial state
         terminal.clear()?;
         
         loop {
+            if let Some(signal) = signals.take() {
+                log::info!("Quitting on signal {}", signal);
+                self.quit_requested = true;
+            }
+            if self.quit_requested {
+                break;
+            }
+
             // If we need a full redraw (e.g., after launching a game), clear the terminal
             if self.needs_redraw {
                 terminal.clear()?;
                 self.needs_redraw = false;
+                // Clearing wipes any image drawn outside ratatui's buffer
+                self.drawn_cover = None;
             }
             
             // Check if status message should be auto-cleared
@@ -130,6 +202,7 @@ impl TuiApp {
             
             // Draw the UI - ratatui's double-buffering will handle efficient updates
             terminal.draw(|f| self.ui(f))?;
+            self.draw_cover_graphics()?;
 
             // Poll for events with a timeout to allow status message clearing
             if event::poll(std::time::Duration::from_millis(100))? {
@@ -155,6 +228,8 @@ impl TuiApp {
                                 break;
                             }
                         }
+                        InputMode::EditingLaunchOptions => self.handle_launch_options_input(key.code).await?,
+                        InputMode::EditingSearchFilters => self.handle_search_filters_input(key.code).await?,
                     }
                 }
             }
@@ -167,36 +242,60 @@ impl TuiApp {
             KeyCode::Char('q') => return Ok(true),
             KeyCode::Tab => {
                 // Exit dialogs when switching tabs
-                if self.state == AppState::GameDetails || self.state == AppState::SaveFilesDialog {
-                    self.state = AppState::Browse;
-                    self.current_game_details = None;
+                match self.state {
+                    AppState::GameDetails | AppState::SaveFilesDialog => self.close_game_details(),
+                    AppState::TranscriptsDialog | AppState::TranscriptView => self.close_transcripts(),
+                    AppState::FormatPicker => self.close_format_picker(),
+                    _ => {}
                 }
                 
-                // Calculate next tab, skipping Browse tab (0) if offline
-                if self.is_online {
-                    self.current_tab = (self.current_tab + 1) % 2; // Only 2 tabs now
-                } else {
-                    // When offline, only My Games tab (which is now tab 0 in offline mode)
-                    self.current_tab = 0;
-                }
+                self.current_tab = (self.current_tab + 1) % 2;
                 self.switch_tab().await?;
             }
             KeyCode::Char('s') => {
-                if self.is_online {
-                    self.input_mode = InputMode::Searching;
-                    self.search_input.clear();
-                } else {
-                    self.set_status_message("Search unavailable - no network connection".to_string());
-                }
+                // Offline searches are answered from the response cache
+                self.input_mode = InputMode::Searching;
+                self.search_input.clear();
             }
             KeyCode::Up => self.move_selection_up().await?,
             KeyCode::Down => self.move_selection_down().await?,
             KeyCode::Enter => self.handle_enter().await?,
             KeyCode::Char('d') => self.handle_download().await?,
+            KeyCode::Char('i') if self.state != AppState::GameDetails && self.current_tab == 1 => {
+                self.handle_view_local_details().await?
+            }
             KeyCode::Char('i') => self.handle_import().await?,
             KeyCode::Char('x') => self.handle_delete().await?,
+            KeyCode::Char('u') if self.current_tab == 1 => self.handle_undo_delete().await?,
             KeyCode::Char('v') => self.handle_view_saves().await?,
+            KeyCode::Char('t') if self.current_tab == 1 && self.state != AppState::GameDetails => {
+                self.handle_view_transcripts()
+            }
+            KeyCode::Char('e') if matches!(self.state, AppState::TranscriptsDialog | AppState::TranscriptView) => {
+                self.export_selected_transcript()
+            }
+            KeyCode::PageUp if self.state == AppState::TranscriptView => {
+                self.transcript_scroll = self.transcript_scroll.saturating_sub(20);
+            }
+            KeyCode::PageDown if self.state == AppState::TranscriptView => {
+                self.transcript_scroll = self.transcript_scroll.saturating_add(20);
+            }
+            KeyCode::Char('a') if self.current_tab == 0 && self.state == AppState::Browse => {
+                self.toggle_show_all_results()
+            }
+            KeyCode::Char('f') if self.current_tab == 0 && self.state == AppState::Browse => {
+                self.search_filters_form = Some(SearchFiltersForm::new(&self.search_filters));
+                self.input_mode = InputMode::EditingSearchFilters;
+            }
             KeyCode::Char('r') => self.refresh_current_view().await?,
+            KeyCode::Char('o') if self.state == AppState::GameDetails => self.handle_edit_launch_options(),
+            KeyCode::Char('c') if self.state == AppState::GameDetails => {
+                if self.current_cover.is_some() {
+                    self.cover_enlarged = !self.cover_enlarged;
+                } else {
+                    self.set_status_message("No cover art for this game".to_string());
+                }
+            }
             KeyCode::Esc => self.handle_escape(),
             _ => {}
         }
@@ -213,12 +312,13 @@ impl TuiApp {
                 self.input_mode = InputMode::Normal;
                 self.search_input.clear();
             }
-            KeyCode::Backspace => {
-                self.search_input.pop();
-            }
-            KeyCode::Char(c) => {
-                self.search_input.push(c);
-            }
+            KeyCode::Backspace => self.search_input.backspace(),
+            KeyCode::Delete => self.search_input.delete(),
+            KeyCode::Left => self.search_input.move_left(),
+            KeyCode::Right => self.search_input.move_right(),
+            KeyCode::Home => self.search_input.move_home(),
+            KeyCode::End => self.search_input.move_end(),
+            KeyCode::Char(c) => self.search_input.insert(c),
             _ => {}
         }
         Ok(false)
@@ -228,11 +328,13 @@ impl TuiApp {
         match key {
             KeyCode::Char('y') | KeyCode::Enter => {
                 self.input_mode = InputMode::Normal;
-                // Handle confirmed action
-                self.set_status_message("Action confirmed".to_string());
+                if let Some(action) = self.pending_action.take() {
+                    self.run_pending_action(action).await?;
+                }
             }
             KeyCode::Char('n') | KeyCode::Esc => {
                 self.input_mode = InputMode::Normal;
+                self.pending_action = None;
                 self.set_status_message("Action cancelled".to_string());
             }
             _ => {}
@@ -241,10 +343,34 @@ impl TuiApp {
     }
 
     async fn move_selection_up(&mut self) -> Result<()> {
+        match self.state {
+            AppState::TranscriptView => {
+                self.transcript_scroll = self.transcript_scroll.saturating_sub(1);
+                return Ok(());
+            }
+            AppState::TranscriptsDialog => {
+                let i = match self.transcript_selection.selected() {
+                    Some(0) | None => self.transcripts.len().saturating_sub(1),
+                    Some(i) => i - 1,
+                };
+                self.transcript_selection.select(Some(i));
+                return Ok(());
+            }
+            AppState::FormatPicker => {
+                let i = match self.format_selection.selected() {
+                    Some(0) | None => self.format_options.len().saturating_sub(1),
+                    Some(i) => i - 1,
+                };
+                self.format_selection.select(Some(i));
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // Handle game details view - navigate to previous game
         if self.state == AppState::GameDetails {
             match self.current_tab {
-                0 if self.is_online => {
+                0 => {
                     // Browse tab - move through search results
                     if let Some(i) = self.search_selection.selected() {
                         let new_i = if i == 0 {
@@ -262,7 +388,7 @@ impl TuiApp {
                     }
                 }
                 _ => {
-                    // My Games tab or offline mode - not implemented yet as those navigate to launch
+                    // My Games tab - not implemented yet as those navigate to launch
                 }
             }
             return Ok(());
@@ -286,33 +412,18 @@ impl TuiApp {
         
         match self.current_tab {
             0 => {
-                if self.is_online {
-                    // Browse tab
-                    let i = match self.search_selection.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                self.search_results.len().saturating_sub(1)
-                            } else {
-                                i - 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.search_selection.select(Some(i));
-                } else {
-                    // My Games when offline
-                    let i = match self.downloaded_selection.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                self.downloaded_games.len().saturating_sub(1)
-                            } else {
-                                i - 1
-                            }
+                // Browse tab
+                let i = match self.search_selection.selected() {
+                    Some(i) => {
+                        if i == 0 {
+                            self.search_results.len().saturating_sub(1)
+                        } else {
+                            i - 1
                         }
-                        None => 0,
-                    };
-                    self.downloaded_selection.select(Some(i));
-                }
+                    }
+                    None => 0,
+                };
+                self.search_selection.select(Some(i));
             }
             1 => {
                 // My Games tab
@@ -334,10 +445,34 @@ impl TuiApp {
     }
 
     async fn move_selection_down(&mut self) -> Result<()> {
+        match self.state {
+            AppState::TranscriptView => {
+                self.transcript_scroll = self.transcript_scroll.saturating_add(1);
+                return Ok(());
+            }
+            AppState::TranscriptsDialog => {
+                let i = match self.transcript_selection.selected() {
+                    Some(i) if i + 1 < self.transcripts.len() => i + 1,
+                    _ => 0,
+                };
+                self.transcript_selection.select(Some(i));
+                return Ok(());
+            }
+            AppState::FormatPicker => {
+                let i = match self.format_selection.selected() {
+                    Some(i) if i + 1 < self.format_options.len() => i + 1,
+                    _ => 0,
+                };
+                self.format_selection.select(Some(i));
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // Handle game details view - navigate to next game
         if self.state == AppState::GameDetails {
             match self.current_tab {
-                0 if self.is_online => {
+                0 => {
                     // Browse tab - move through search results
                     if let Some(i) = self.search_selection.selected() {
                         let new_i = if i >= self.search_results.len().saturating_sub(1) {
@@ -355,7 +490,7 @@ impl TuiApp {
                     }
                 }
                 _ => {
-                    // My Games tab or offline mode - not implemented yet
+                    // My Games tab - not implemented yet
                 }
             }
             return Ok(());
@@ -379,37 +514,22 @@ impl TuiApp {
         
         match self.current_tab {
             0 => {
-                if self.is_online {
-                    // Browse tab
-                    let i = match self.search_selection.selected() {
-                        Some(i) => {
-                            if i >= self.search_results.len().saturating_sub(1) {
-                                // At the end of the list - trigger loading more if available
-                                if self.has_more_search_results && !self.loading {
-                                    self.should_load_next_page = true;
-                                }
-                                i // Stay at current position
-                            } else {
-                                i + 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.search_selection.select(Some(i));
-                } else {
-                    // My Games when offline
-                    let i = match self.downloaded_selection.selected() {
-                        Some(i) => {
-                            if i >= self.downloaded_games.len().saturating_sub(1) {
-                                0
-                            } else {
-                                i + 1
+                // Browse tab
+                let i = match self.search_selection.selected() {
+                    Some(i) => {
+                        if i >= self.search_results.len().saturating_sub(1) {
+                            // At the end of the list - trigger loading more if available
+                            if self.has_more_search_results && !self.loading {
+                                self.should_load_next_page = true;
                             }
+                            i // Stay at current position
+                        } else {
+                            i + 1
                         }
-                        None => 0,
-                    };
-                    self.downloaded_selection.select(Some(i));
-                }
+                    }
+                    None => 0,
+                };
+                self.search_selection.select(Some(i));
             }
             1 => {
                 // My Games tab
@@ -431,6 +551,15 @@ impl TuiApp {
     }
 
     async fn handle_enter(&mut self) -> Result<()> {
+        if self.state == AppState::TranscriptsDialog {
+            self.open_selected_transcript();
+            return Ok(());
+        }
+
+        if self.state == AppState::FormatPicker {
+            return self.launch_with_chosen_format().await;
+        }
+
         // Handle save files dialog separately
         if self.state == AppState::SaveFilesDialog {
             if let Some(i) = self.save_selection.selected() {
@@ -443,20 +572,11 @@ impl TuiApp {
         
         match self.current_tab {
             0 => {
-                if self.is_online {
-                    // Browse games - show details or download
-                    if let Some(i) = self.search_selection.selected() {
-                        if let Some(game) = self.search_results.get(i) {
-                            let tuid = game.tuid.clone();
-                            self.show_game_details(&tuid).await?;
-                        }
-                    }
-                } else {
-                    // When offline, tab 0 is My Games
-                    if let Some(i) = self.downloaded_selection.selected() {
-                        if let Some(game) = self.downloaded_games.get(i).cloned() {
-                            self.launch_game(&game).await?;
-                        }
+                // Browse games - show details or download
+                if let Some(i) = self.search_selection.selected() {
+                    if let Some(game) = self.search_results.get(i) {
+                        let tuid = game.tuid.clone();
+                        self.show_game_details(&tuid).await?;
                     }
                 }
             }
@@ -493,43 +613,67 @@ impl TuiApp {
         if self.current_tab == 1 {
             if let Some(i) = self.downloaded_selection.selected() {
                 if let Some(game) = self.downloaded_games.get(i) {
-                    let tuid = game.tuid.clone();
-                    let title = game.title.clone();
-                    
-                    match self.storage.remove_game(&tuid) {
-                        Ok(_) => {
-                            self.set_status_message(format!("Deleted '{}'", title));
-                            self.refresh_downloaded_games().await?;
-                        }
-                        Err(e) => {
-                            self.set_status_message(format!("Failed to delete game: {}", e));
-                        }
-                    }
+                    let action = PendingAction::DeleteGame {
+                        tuid: game.tuid.clone(),
+                        title: game.title.clone(),
+                    };
+                    self.request_confirmation(action);
                 }
             }
         }
         Ok(())
     }
 
-    async fn switch_tab(&mut self) -> Result<()> {
-        match self.current_tab {
-            0 => {
-                if self.is_online {
-                    // Browse tab - refresh search results if empty
-                    if self.search_results.is_empty() {
-                        self.browse_popular_games().await?;
+    /// Carry out an action the user has just confirmed
+    async fn run_pending_action(&mut self, action: PendingAction) -> Result<()> {
+        match action {
+            PendingAction::DeleteGame { tuid, title } => {
+                match self.storage.trash_game(&tuid) {
+                    Ok(_) => {
+                        self.set_status_message(format!("Moved '{}' to trash - press u to undo", title));
+                        self.refresh_downloaded_games().await?;
+                    }
+                    Err(e) => {
+                        self.set_status_message(format!("Failed to delete game: {}", e));
                     }
-                } else {
-                    // When offline, tab 0 is My Games
-                    self.refresh_downloaded_games().await?;
                 }
             }
-            1 => {
-                // My Games tab (when online) or doesn't exist (when offline)
-                if self.is_online {
-                    self.refresh_downloaded_games().await?;
-                }
+        }
+        Ok(())
+    }
+
+    /// Restore the most recently deleted game from the trash
+    async fn handle_undo_delete(&mut self) -> Result<()> {
+        let latest = match self.storage.list_trash() {
+            Ok(entries) => entries.into_iter().next(),
+            Err(e) => {
+                self.set_status_message(format!("Failed to read trash: {}", e));
+                return Ok(());
             }
+        };
+
+        let Some(entry) = latest else {
+            self.set_status_message("Nothing to undo".to_string());
+            return Ok(());
+        };
+
+        match self.storage.restore_from_trash(&entry.id) {
+            Ok(game) => {
+                self.set_status_message(format!("Restored '{}'", game.title));
+                self.refresh_downloaded_games().await?;
+            }
+            Err(e) => {
+                self.set_status_message(format!("Failed to restore game: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    async fn switch_tab(&mut self) -> Result<()> {
+        match self.current_tab {
+            // Browse tab - refresh search results if empty
+            0 if self.search_results.is_empty() => self.browse_popular_games().await?,
+            1 => self.refresh_downloaded_games().await?,
             _ => {}
         }
         Ok(())
@@ -537,7 +681,7 @@ impl TuiApp {
 
     async fn handle_view_saves(&mut self) -> Result<()> {
         // Only works in My Games tab
-        if self.current_tab == 1 || (self.current_tab == 0 && !self.is_online) {
+        if self.current_tab == 1 {
             if let Some(i) = self.downloaded_selection.selected() {
                 if let Some(game) = self.downloaded_games.get(i) {
                     let tuid = game.tuid.clone();
@@ -553,6 +697,136 @@ impl TuiApp {
         Ok(())
     }
 
+    fn handle_view_transcripts(&mut self) {
+        let Some(game) = self.downloaded_selection.selected().and_then(|i| self.downloaded_games.get(i)) else {
+            self.set_status_message("No game selected".to_string());
+            return;
+        };
+
+        match transcript::list(&self.storage.transcript_dir(game)) {
+            Ok(transcripts) => {
+                self.transcript_selection = ListState::default();
+                if !transcripts.is_empty() {
+                    self.transcript_selection.select(Some(0));
+                }
+                self.transcripts = transcripts;
+                self.state = AppState::TranscriptsDialog;
+            }
+            Err(e) => self.set_status_message(format!("Failed to list transcripts: {}", e)),
+        }
+    }
+
+    fn open_selected_transcript(&mut self) {
+        let Some(transcript) = self.transcript_selection.selected().and_then(|i| self.transcripts.get(i)) else {
+            return;
+        };
+
+        match transcript.to_plain_text() {
+            Ok(text) => {
+                self.transcript_text = text;
+                self.transcript_scroll = 0;
+                self.state = AppState::TranscriptView;
+            }
+            Err(e) => self.set_status_message(format!("Failed to read transcript: {}", e)),
+        }
+    }
+
+    fn export_selected_transcript(&mut self) {
+        let Some(transcript) = self.transcript_selection.selected().and_then(|i| self.transcripts.get(i)) else {
+            return;
+        };
+
+        match transcript.export_text() {
+            Ok(path) => self.set_status_message(format!("Saved {}", path.display())),
+            Err(e) => self.set_status_message(format!("Failed to export transcript: {}", e)),
+        }
+    }
+
+    fn close_transcripts(&mut self) {
+        self.state = AppState::Browse;
+        self.transcripts.clear();
+        self.transcript_selection = ListState::default();
+        self.transcript_text.clear();
+    }
+
+    /// Ask the player which format a game is, listing each format's interpreter
+    fn open_format_picker(&mut self, game: &LocalGame) {
+        self.format_options = GameFormat::PLAYABLE
+            .into_iter()
+            .map(|format| (format, self.launcher.interpreter_path(format).is_some()))
+            .collect();
+        // Start on the first format that can actually be played
+        let first_installed = self.format_options.iter().position(|(_, installed)| *installed);
+        self.format_selection.select(Some(first_installed.unwrap_or(0)));
+        self.format_picker_game = Some(game.clone());
+        self.state = AppState::FormatPicker;
+        self.set_status_message(format!("Could not detect the format of '{}' - choose one", game.title));
+    }
+
+    fn close_format_picker(&mut self) {
+        self.state = AppState::Browse;
+        self.format_picker_game = None;
+        self.format_options.clear();
+        self.format_selection = ListState::default();
+    }
+
+    /// Launch the game as the format picked, remembering it if the game ran
+    ///
+    /// A wrong pick usually makes the interpreter fail, so the choice is
+    /// only saved once the game has ended normally.
+    async fn launch_with_chosen_format(&mut self) -> Result<()> {
+        let chosen = self.format_selection.selected().and_then(|i| self.format_options.get(i)).copied();
+        let (Some((format, _)), Some(game)) = (chosen, self.format_picker_game.clone()) else {
+            return Ok(());
+        };
+        self.close_format_picker();
+
+        if self.launch_game_as(&game, Some(format)).await? {
+            if let Err(e) = self.launcher.remember_format(&game.file_path, format, None) {
+                log::warn!("Failed to remember format for {}: {:#}", game.file_path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_view_local_details(&mut self) -> Result<()> {
+        if let Some(i) = self.downloaded_selection.selected() {
+            if let Some(game) = self.downloaded_games.get(i).cloned() {
+                self.current_story_info = match inspect::inspect(&game.file_path) {
+                    Ok(info) => Some((game.tuid.clone(), info)),
+                    Err(e) => {
+                        log::warn!("Failed to inspect {}: {:#}", game.file_path.display(), e);
+                        None
+                    }
+                };
+
+                // Prefer the snapshot saved with the game - it needs no network
+                match self.storage.load_game_details(&game) {
+                    Ok(Some(details)) => {
+                        self.current_details_stale = false;
+                        self.current_game_details = Some(details);
+                        self.state = AppState::GameDetails;
+                        self.load_current_cover(&game.tuid).await;
+                    }
+                    Ok(None) => {
+                        // Older downloads have no snapshot - fall back to IFDB or its cache
+                        self.show_game_details(&game.tuid).await?;
+                    }
+                    Err(e) => {
+                        if self.debug {
+                            log::error!("Failed to load saved details for {}: {}", game.tuid, e);
+                        }
+                        self.show_game_details(&game.tuid).await?;
+                    }
+                }
+            }
+        } else {
+            self.set_status_message("No game selected".to_string());
+        }
+        Ok(())
+    }
+
     async fn handle_import(&mut self) -> Result<()> {
         // Only allow import from game details view for commercial games
         if self.state == AppState::GameDetails {
@@ -616,20 +890,128 @@ impl TuiApp {
         Ok(false)
     }
 
+    /// The downloaded game whose details are being viewed, if any
+    fn current_local_game(&self) -> Option<&LocalGame> {
+        let tuid = self.current_game_details.as_ref()?.ifdb.as_ref()?.tuid.as_str();
+        self.downloaded_games.iter().find(|g| g.tuid == tuid)
+    }
+
+    fn handle_edit_launch_options(&mut self) {
+        match self.current_local_game() {
+            Some(game) => {
+                self.launch_options_form = Some(LaunchOptionsForm::new(game));
+                self.input_mode = InputMode::EditingLaunchOptions;
+            }
+            None => self.set_status_message("Only games in My Games have launch options".to_string()),
+        }
+    }
+
+    async fn handle_launch_options_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = self.launch_options_form.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let last_field = LaunchOptionsForm::FIELDS.len() - 1;
+
+        match key {
+            KeyCode::Enter => {
+                let options = match form.to_options() {
+                    Ok(options) => options,
+                    Err(e) => {
+                        self.set_status_message(e.to_string());
+                        return Ok(());
+                    }
+                };
+                let tuid = form.tuid.clone();
+                self.input_mode = InputMode::Normal;
+                self.launch_options_form = None;
+
+                match self.storage.set_launch_options(&tuid, options) {
+                    Ok(()) => {
+                        self.set_status_message("Launch options saved".to_string());
+                        self.refresh_downloaded_games().await?;
+                    }
+                    Err(e) => self.set_status_message(format!("Failed to save launch options: {}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.launch_options_form = None;
+                self.set_status_message("Launch options unchanged".to_string());
+            }
+            KeyCode::Up | KeyCode::BackTab => form.field = form.field.checked_sub(1).unwrap_or(last_field),
+            KeyCode::Down | KeyCode::Tab => form.field = if form.field == last_field { 0 } else { form.field + 1 },
+            KeyCode::Left if form.field == 0 => form.cycle_format(false),
+            KeyCode::Right if form.field == 0 => form.cycle_format(true),
+            KeyCode::Char(c) => {
+                if let Some(text) = form.text_mut() {
+                    text.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = form.text_mut() {
+                    text.pop();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn handle_search_filters_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = self.search_filters_form.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let last_field = SearchFiltersForm::FIELDS.len() - 1;
+
+        match key {
+            KeyCode::Enter => {
+                let filters = match form.to_filters() {
+                    Ok(filters) => filters,
+                    Err(e) => {
+                        self.set_status_message(e.to_string());
+                        return Ok(());
+                    }
+                };
+                self.input_mode = InputMode::Normal;
+                self.search_filters_form = None;
+                self.search_filters = filters;
+                self.perform_search().await?;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.search_filters_form = None;
+                self.set_status_message("Search filters unchanged".to_string());
+            }
+            KeyCode::Up | KeyCode::BackTab => form.field = form.field.checked_sub(1).unwrap_or(last_field),
+            KeyCode::Down | KeyCode::Tab => form.field = if form.field == last_field { 0 } else { form.field + 1 },
+            KeyCode::Left => form.cycle(false),
+            KeyCode::Right => form.cycle(true),
+            KeyCode::Char(c) => {
+                if let Some(text) = form.text_mut() {
+                    text.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = form.text_mut() {
+                    text.pop();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn import_game_file(&mut self, tuid: &str, file_path: &str) -> Result<()> {
         if self.debug {
             log::debug!("Importing game file from: {}", file_path);
         }
 
         // Expand home directory if path starts with ~
-        let expanded_path = if file_path.starts_with("~/") {
-            if let Some(home) = std::env::var_os("HOME") {
-                std::path::PathBuf::from(home).join(&file_path[2..])
-            } else {
-                std::path::PathBuf::from(file_path)
-            }
-        } else {
-            std::path::PathBuf::from(file_path)
+        let expanded_path = match (file_path.strip_prefix("~/"), std::env::var_os("HOME")) {
+            (Some(rest), Some(home)) => std::path::PathBuf::from(home).join(rest),
+            _ => std::path::PathBuf::from(file_path),
         };
 
         // Check if file exists
@@ -680,7 +1062,7 @@ impl TuiApp {
                     let link = details.ifdb
                         .as_ref()
                         .map(|i| i.link.clone())
-                        .unwrap_or_else(|| format!("https://ifdb.org/viewgame?id={}", tuid));
+                        .unwrap_or_else(|| format!("{}/viewgame?id={}", self.ifdb_client.base_url(), tuid));
 
                     let game = Game {
                         tuid: tuid.to_string(),
@@ -699,13 +1081,15 @@ impl TuiApp {
 
                     // Store the file using add_game_with_cover (async version)
                     match self.storage.add_game_with_cover(&game, Some(details), &bytes, extension).await {
-                        Ok(_) => {
-                            self.set_status_message(format!("Game '{}' imported successfully!", game_title));
+                        Ok(local) => {
+                            self.set_status_message(added_game_message(
+                                &local,
+                                format!("Game '{}' imported successfully!", game_title),
+                            ));
                             self.refresh_downloaded_games().await?;
                             
                             // Return to browse view
-                            self.state = AppState::Browse;
-                            self.current_game_details = None;
+                            self.close_game_details();
                         }
                         Err(e) => {
                             if self.debug {
@@ -731,27 +1115,26 @@ impl TuiApp {
     }
 
     async fn perform_search(&mut self) -> Result<()> {
-        if self.search_input.trim().is_empty() {
+        if self.search_input.as_str().trim().is_empty() && self.search_filters.is_empty() {
             self.browse_popular_games().await?;
         } else {
             self.loading = true;
             self.current_search_page = 1;
-            self.set_status_message(format!("Searching for '{}'...", self.search_input));
-            let options = SearchOptions::new(&self.search_input)
+            self.set_status_message(format!("Searching for '{}'...", self.search_input.as_str()));
+            let options = SearchOptions::new(self.search_input.as_str())
                 .with_limit(50)
                 .with_page(1)
-                .with_glk_formats();  // Filter for playable formats
+                .with_glk_formats()  // Filter for playable formats
+                .with_filters(self.search_filters.clone());
             
             match self.ifdb_client.search_games(&options).await {
                 Ok(games) => {
-                    self.has_more_search_results = games.len() >= 50;
-                    self.search_results = games;
-                    self.search_selection.select(if self.search_results.is_empty() {
-                        None
-                    } else {
-                        Some(0)
-                    });
-                    self.set_status_message(format!("Found {} games", self.search_results.len()));
+                    self.has_more_search_results = games.data.len() >= 50;
+                    self.search_results_stale = games.stale;
+                    self.set_search_results(games.data);
+                    let stale_note = if self.search_results_stale { " (cached, may be stale)" } else { "" };
+                    self.set_status_message(format!("Found {} games{}{}",
+                        self.search_results.len(), self.hidden_results_note(), stale_note));
                 }
                 Err(e) => {
                     self.set_status_message(format!("Search failed: {}", e));
@@ -771,19 +1154,23 @@ impl TuiApp {
         self.current_search_page += 1;
         self.set_status_message(format!("Loading page {}...", self.current_search_page));
         
-        let options = SearchOptions::new(&self.search_input)
+        let options = SearchOptions::new(self.search_input.as_str())
             .with_limit(50)
             .with_page(self.current_search_page)
-            .with_glk_formats();
+            .with_glk_formats()
+            .with_filters(self.search_filters.clone());
         
         match self.ifdb_client.search_games(&options).await {
             Ok(mut games) => {
-                self.has_more_search_results = games.len() >= 50;
+                self.has_more_search_results = games.data.len() >= 50;
+                self.search_results_stale |= games.stale;
                 let prev_len = self.search_results.len();
-                self.search_results.append(&mut games);
-                self.set_status_message(format!("Loaded {} more games (total: {})", 
+                self.all_search_results.append(&mut games.data);
+                self.apply_result_filter();
+                self.set_status_message(format!("Loaded {} more games (total: {}{})", 
                     self.search_results.len() - prev_len, 
-                    self.search_results.len()));
+                    self.search_results.len(),
+                    self.hidden_results_note()));
             }
             Err(e) => {
                 self.current_search_page -= 1; // Revert page on error
@@ -800,22 +1187,89 @@ impl TuiApp {
         self.current_search_page = 1;
         match self.ifdb_client.browse_games(Some("rating")).await {
             Ok(games) => {
-                self.has_more_search_results = games.len() >= 50;
-                self.search_results = games;
-                self.search_selection.select(if self.search_results.is_empty() {
-                    None
-                } else {
-                    Some(0)
-                });
+                self.has_more_search_results = games.data.len() >= 50;
+                self.search_results_stale = games.stale;
+                self.set_search_results(games.data);
             }
             Err(e) => {
-                self.set_status_message(format!("Failed to load games: {}", e));
+                // Nothing cached yet is expected when starting offline
+                if self.is_online {
+                    self.set_status_message(format!("Failed to load games: {}", e));
+                }
             }
         }
         self.loading = false;
         Ok(())
     }
 
+    /// Replace the search results with a new first page
+    fn set_search_results(&mut self, games: Vec<Game>) {
+        self.all_search_results = games;
+        self.search_selection.select(None);
+        self.apply_result_filter();
+    }
+
+    /// Rebuild the browse list and its badges from every result
+    ///
+    /// Results are hidden unless glkcli can play them with the interpreters
+    /// installed now, or everything is being shown. The selection stays on
+    /// the same game where it is still listed.
+    fn apply_result_filter(&mut self) {
+        let selected_tuid = self.search_selection.selected()
+            .and_then(|i| self.search_results.get(i))
+            .map(|game| game.tuid.clone());
+
+        // Each game's cached details are read once; fetching details later
+        // updates the flag
+        for game in &self.all_search_results {
+            if !self.commercial_games.contains_key(&game.tuid) {
+                let commercial = self.ifdb_client
+                    .cached_game_details(&game.tuid)
+                    .is_some_and(|details| details.is_commercial());
+                self.commercial_games.insert(game.tuid.clone(), commercial);
+            }
+        }
+
+        let (games, badges): (Vec<Game>, Vec<ResultBadges>) = self.all_search_results
+            .iter()
+            .filter_map(|game| {
+                let badges = ResultBadges {
+                    playability: Playability::of(game.devsys.as_deref(), &self.installed_formats),
+                    commercial: self.commercial_games.get(&game.tuid).copied().unwrap_or(false),
+                    downloaded: self.downloaded_games.iter().any(|local| local.tuid == game.tuid),
+                };
+                (self.show_all_results || badges.playability.is_shown_by_default())
+                    .then(|| (game.clone(), badges))
+            })
+            .unzip();
+        self.search_results = games;
+        self.search_badges = badges;
+
+        let selected = selected_tuid
+            .and_then(|tuid| self.search_results.iter().position(|game| game.tuid == tuid))
+            .or(if self.search_results.is_empty() { None } else { Some(0) });
+        self.search_selection.select(selected);
+    }
+
+    /// Show or hide results glkcli cannot play
+    fn toggle_show_all_results(&mut self) {
+        self.show_all_results = !self.show_all_results;
+        self.apply_result_filter();
+        self.set_status_message(if self.show_all_results {
+            format!("Showing all {} results", self.search_results.len())
+        } else {
+            format!("Showing {} playable results{}", self.search_results.len(), self.hidden_results_note())
+        });
+    }
+
+    /// Status note saying how many results the filter hides
+    fn hidden_results_note(&self) -> String {
+        match self.all_search_results.len() - self.search_results.len() {
+            0 => String::new(),
+            hidden => format!(", {} hidden - press a to show all", hidden),
+        }
+    }
+
     async fn show_game_details(&mut self, tuid: &str) -> Result<()> {
         if self.debug {
             log::debug!("Fetching game details for TUID: {}", tuid);
@@ -827,8 +1281,17 @@ impl TuiApp {
                 if self.debug {
                     log::debug!("Successfully fetched game details for {}: {:?}", tuid, details);
                 }
-                self.current_game_details = Some(details);
+                // Keep the snapshot of downloaded games current
+                if !details.stale && self.storage.is_game_downloaded(tuid).unwrap_or(false) {
+                    if let Err(e) = self.storage.update_game_details(tuid, &details.data) {
+                        log::warn!("Failed to update saved details for {}: {}", tuid, e);
+                    }
+                }
+                self.commercial_games.insert(tuid.to_string(), details.data.is_commercial());
+                self.current_details_stale = details.stale;
+                self.current_game_details = Some(details.data);
                 self.state = AppState::GameDetails;
+                self.load_current_cover(tuid).await;
             }
             Err(e) => {
                 if self.debug {
@@ -861,10 +1324,11 @@ impl TuiApp {
         }
         
         match self.ifdb_client.get_game_details(&game.tuid).await {
-            Ok(details) => {
+            Ok(Fetched { data: details, .. }) => {
                 if self.debug {
                     log::debug!("Successfully fetched game details: {:?}", details);
                 }
+                self.commercial_games.insert(game.tuid.clone(), details.is_commercial());
                 
                 // Check if game is commercial
                 if details.is_commercial() {
@@ -892,21 +1356,28 @@ impl TuiApp {
                         let is_acceptable_format = |link: &&crate::ifdb::DownloadLink| {
                             if let Some(format) = &link.format {
                                 let format_lower = format.to_lowercase();
-                                // Exclude generic formats that might be platform-specific
+                                // Exclude generic formats that might be platform-specific,
+                                // and Quest, which has no terminal player
                                 !matches!(format_lower.as_str(), 
-                                    "storyfile" | "hypertextgame" | "executable"
+                                    "storyfile" | "executable" | "quest"
                                 )
                             } else {
                                 // If no format specified, we'll allow it (might be a direct download)
                                 true
                             }
                         };
+                        // Web pages are opened in a browser, so story files are preferred
+                        let is_story = |link: &&crate::ifdb::DownloadLink| {
+                            !link.format.as_deref().is_some_and(|f| f.eq_ignore_ascii_case("hypertextgame"))
+                        };
                         
                         // Find the best download link (prioritize game files with acceptable formats)
                         let download_link = downloads.links.iter()
                             .filter(is_acceptable_format)
+                            .filter(is_story)
                             .find(|link| link.is_game)
-                            .or_else(|| downloads.links.iter().filter(is_acceptable_format).next());
+                            .or_else(|| downloads.links.iter().filter(is_acceptable_format).find(|link| link.is_game))
+                            .or_else(|| downloads.links.iter().find(is_acceptable_format));
 
                         if let Some(link) = download_link {
                             if self.debug {
@@ -933,8 +1404,11 @@ impl TuiApp {
                                     }
 
                                     match self.storage.add_game_with_cover(game, Some(&details), &bytes, extension).await {
-                                        Ok(_) => {
-                                            self.set_status_message("Game downloaded successfully".to_string());
+                                        Ok(local) => {
+                                            self.set_status_message(added_game_message(
+                                                &local,
+                                                "Game downloaded successfully".to_string(),
+                                            ));
                                             self.refresh_downloaded_games().await?;
                                         }
                                         Err(e) => {
@@ -949,12 +1423,16 @@ impl TuiApp {
                                     self.set_status_message(format!("Download failed: {}", e));
                                 }
                             }
+                        } else if let Some(url) = details.play_online_url() {
+                            self.add_online_game(game, &details, url).await?;
                         } else {
                             if self.debug {
                                 log::warn!("No download links found in game details");
                             }
                             self.set_status_message("No download links found".to_string());
                         }
+                    } else if let Some(url) = details.play_online_url() {
+                        self.add_online_game(game, &details, url).await?;
                     } else {
                         if self.debug {
                             log::warn!("No download section found in IFDB data");
@@ -980,7 +1458,25 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Add a game that can only be played online as a browser shortcut
+    async fn add_online_game(&mut self, game: &Game, details: &GameDetails, url: &str) -> Result<()> {
+        let shortcut = web::shortcut_contents(url);
+        match self.storage.add_game_with_cover(game, Some(details), shortcut.as_bytes(), "url").await {
+            Ok(_) => {
+                self.set_status_message(format!("Added '{}' - it is played online in your browser", game.title));
+                self.refresh_downloaded_games().await?;
+            }
+            Err(e) => self.set_status_message(format!("Failed to save game: {}", e)),
+        }
+        Ok(())
+    }
+
     async fn launch_game(&mut self, game: &LocalGame) -> Result<()> {
+        self.launch_game_as(game, None).await.map(|_| ())
+    }
+
+    /// Launch a game, as `format` if given, returning whether it ended normally
+    async fn launch_game_as(&mut self, game: &LocalGame, format: Option<GameFormat>) -> Result<bool> {
         // Temporarily disable raw mode and restore terminal before launching game
         disable_raw_mode().context("Failed to disable raw mode")?;
         execute!(
@@ -991,7 +1487,17 @@ impl TuiApp {
         ).context("Failed to restore terminal")?;
         
         // Launch the game
-        let launch_result = self.launcher.detect_and_run(&game.file_path, false);
+        let transcript_dir = self.settings
+            .transcripts_enabled()
+            .then(|| self.storage.transcript_dir(game));
+        let mut launcher = self.launcher.clone();
+        if format.is_some() {
+            launcher = launcher.with_format_override(format);
+        }
+        let launch_result = launcher
+            .with_launch_options(&game.launch_options)
+            .with_game(&game.tuid, self.storage.get_save_dir(&game.tuid))
+            .launch(&game.file_path, false, transcript_dir.as_deref());
         
         // Re-enable raw mode and alternate screen after game exits
         enable_raw_mode().context("Failed to re-enable raw mode")?;
@@ -1002,23 +1508,57 @@ impl TuiApp {
             crossterm::cursor::Hide
         ).context("Failed to re-setup terminal")?;
         
-        match launch_result {
-            Ok(_) => {
-                // Record that the game was played
-                let _ = self.storage.record_game_played(&game.tuid);
-                self.set_status_message("Game ended successfully".to_string());
+        let ended_normally = match launch_result {
+            Ok(outcome) => {
+                // Record the session whether or not the interpreter exited cleanly
+                let mut session = PlaySession::new(
+                    &outcome.interpreter,
+                    outcome.started_at,
+                    outcome.ended_at,
+                    outcome.exit_code,
+                );
+                session.transcript = outcome.transcript.as_ref().map(|t| t.output.clone());
+                if let Err(e) = self.storage.record_play_session(&game.tuid, session) {
+                    log::warn!("Failed to record play session: {}", e);
+                }
+
+                // SIGTERM or SIGHUP during the game was meant for glkcli too
+                if outcome.termination_requested() {
+                    self.quit_requested = true;
+                }
+
+                match outcome.check() {
+                    Ok(()) if outcome.format == GameFormat::Web => {
+                        self.set_status_message(format!("Opened '{}' in the browser", game.title))
+                    }
+                    Ok(()) => self.set_status_message(format!(
+                        "Game ended after {}",
+                        format_duration(outcome.duration())
+                    )),
+                    Err(e) => self.set_status_message(format!("Game ended with an error: {}", e)),
+                }
                 // Refresh the game list to update play count
                 let _ = self.refresh_downloaded_games().await;
+                outcome.check().is_ok()
+            }
+            Err(LaunchError::UnknownFormat(_)) => {
+                self.open_format_picker(game);
+                false
             }
             Err(e) => {
-                self.set_status_message(format!("Failed to launch game: {}", e));
+                let message = match e.hint() {
+                    Some(hint) => format!("Failed to launch game: {} - {}", e, hint),
+                    None => format!("Failed to launch game: {}", e),
+                };
+                self.set_status_message(message);
+                false
             }
-        }
+        };
         
         // Mark that we need a full terminal redraw
         self.needs_redraw = true;
         
-        Ok(())
+        Ok(ended_normally)
     }
 
     async fn load_save_file(&mut self, save: &SaveFile) -> Result<()> {
@@ -1038,6 +1578,8 @@ impl TuiApp {
         match self.storage.get_downloaded_games() {
             Ok(games) => {
                 self.downloaded_games = games;
+                // Keep the "downloaded" badges in step with the library
+                self.apply_result_filter();
                 self.downloaded_selection.select(if self.downloaded_games.is_empty() {
                     None
                 } else {
@@ -1072,6 +1614,7 @@ impl TuiApp {
         // Check network connectivity when refreshing
         let was_online = self.is_online;
         self.is_online = self.network.is_connected().await;
+        self.ifdb_client.set_offline(!self.is_online);
         
         if !was_online && self.is_online {
             // Just came online - show message and switch to browse tab
@@ -1081,41 +1624,136 @@ impl TuiApp {
             return Ok(());
         } else if was_online && !self.is_online {
             // Just went offline - show message and switch to downloaded games
-            self.set_status_message("Network connection lost".to_string());
+            self.set_status_message("Network connection lost - showing cached data".to_string());
             self.current_tab = 1;
             return Ok(());
         }
         
         match self.current_tab {
             0 => {
-                if self.is_online {
-                    self.browse_popular_games().await?
-                } else {
-                    self.set_status_message("Cannot refresh - no network connection".to_string());
+                self.browse_popular_games().await?;
+                if !self.is_online {
+                    self.set_status_message("Offline - showing cached games".to_string());
                 }
-            },
-            1 => {
-                if self.is_online {
-                    self.refresh_downloaded_games().await?
-                }
-            },
+            }
+            1 => self.refresh_downloaded_games().await?,
             _ => {}
         }
         Ok(())
     }
 
+    /// Leave the game details view and drop its cover art
+    fn close_game_details(&mut self) {
+        self.state = AppState::Browse;
+        self.current_game_details = None;
+        self.current_cover = None;
+        self.cover_lines = None;
+        self.cover_enlarged = false;
+        // Details just viewed may have shown the game is commercial
+        self.apply_result_filter();
+    }
+
+    /// Load the cover art for the game details being viewed
+    ///
+    /// Downloaded games use the cover saved with them; other games use the
+    /// cover cached from IFDB, fetching it first if we're online.
+    async fn load_current_cover(&mut self, tuid: &str) {
+        self.current_cover = None;
+        self.cover_lines = None;
+        if self.graphics == GraphicsProtocol::None {
+            return;
+        }
+
+        let local_cover = self.storage.get_game(tuid)
+            .ok()
+            .flatten()
+            .and_then(|g| g.cover_art_path)
+            .filter(|p| p.exists());
+
+        let cover_path = match local_cover {
+            Some(path) => Some(path),
+            None => {
+                let url = self.current_game_details
+                    .as_ref()
+                    .and_then(|d| d.ifdb.as_ref())
+                    .and_then(|i| i.coverart.as_ref())
+                    .map(|c| c.url.clone());
+                match url {
+                    Some(url) => match self.ifdb_client.fetch_cover_art(tuid, &url).await {
+                        Ok(path) => Some(path),
+                        Err(e) => {
+                            if self.debug {
+                                log::debug!("No cover art for {}: {}", tuid, e);
+                            }
+                            None
+                        }
+                    },
+                    None => None,
+                }
+            }
+        };
+
+        if let Some(path) = cover_path {
+            match CoverImage::open(&path) {
+                Ok(cover) => self.current_cover = Some(cover),
+                Err(e) => log::warn!("Failed to load cover art {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// Draw image-protocol cover art on top of the frame ratatui just drew
+    ///
+    /// Images are written straight to the terminal, so they are only sent
+    /// when the cover or its position changes. Moving or removing an image
+    /// forces a full redraw to wipe the old one.
+    fn draw_cover_graphics(&mut self) -> Result<()> {
+        let wanted = match (&self.current_cover, self.pending_cover_area.take()) {
+            (Some(cover), Some(area)) if self.graphics.is_image_protocol() => {
+                Some((area, cover.path.clone()))
+            }
+            _ => None,
+        };
+
+        if wanted == self.drawn_cover {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout();
+        if self.drawn_cover.take().is_some() {
+            if let Some(clear) = cover_art::clear_sequence(self.graphics) {
+                write!(stdout, "{}", clear)?;
+            }
+            // The new image (if any) is drawn after the redraw
+            self.needs_redraw = true;
+            stdout.flush()?;
+            return Ok(());
+        }
+
+        if let (Some((area, _)), Some(cover)) = (&wanted, &self.current_cover) {
+            let sequence = cover.escape_sequence(self.graphics, *area, cover_art::cell_pixel_size())?;
+            execute!(stdout, crossterm::cursor::MoveTo(area.x, area.y))?;
+            write!(stdout, "{}", sequence)?;
+            stdout.flush()?;
+            self.drawn_cover = wanted;
+        }
+
+        Ok(())
+    }
+
     fn handle_escape(&mut self) {
-        use ratatui::widgets::ListState;
         match self.state {
-            AppState::GameDetails => {
-                self.state = AppState::Browse;
-                self.current_game_details = None;
-            }
+            AppState::GameDetails => self.close_game_details(),
             AppState::SaveFilesDialog => {
                 self.state = AppState::Browse;
                 self.save_files.clear();
                 self.save_selection = ListState::default();
             }
+            AppState::TranscriptsDialog => self.close_transcripts(),
+            AppState::FormatPicker => self.close_format_picker(),
+            AppState::TranscriptView => {
+                self.state = AppState::TranscriptsDialog;
+                self.transcript_text.clear();
+            }
             _ => {
                 self.status_message = None;
             }
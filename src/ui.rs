@@ -13,8 +13,13 @@ use ratatui::{
 };
 use std::time::SystemTime;
 
-use crate::app::state::{TuiApp, AppState, InputMode};
-use crate::utils::decode_html_entities;
+use crate::app::state::{TuiApp, AppState, InputMode, LaunchOptionsForm, Playability, ResultBadges, SearchFiltersForm};
+use crate::cover_art::{CoverImage, GraphicsProtocol};
+use crate::ifdb::GameDetails;
+use crate::inspect::StoryInfo;
+use crate::launcher::LaunchOptions;
+use crate::storage::LocalGame;
+use crate::utils::{decode_html_entities, format_duration, format_size};
 use crate::border_style::get_border_type;
 
 /// Helper function to create a block with appropriate border type for the terminal
@@ -22,6 +27,33 @@ fn create_block() -> Block<'static> {
     Block::default().border_type(get_border_type())
 }
 
+/// Badges shown after a search result in the browse list
+fn badge_spans(badges: &ResultBadges) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut badge = |text: String, color: Color| {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(format!("[{}]", text), Style::default().fg(color)));
+    };
+
+    match badges.playability {
+        Playability::Playable(_) => badge("playable".to_string(), Color::Green),
+        Playability::Browser => badge("browser".to_string(), Color::Cyan),
+        Playability::NeedsInterpreter(format) => badge(
+            format!("needs {}", format.interpreter().unwrap_or(format.name())),
+            Color::Yellow,
+        ),
+        Playability::Unlisted => badge("unknown system".to_string(), Color::DarkGray),
+        Playability::Unsupported => badge("not playable".to_string(), Color::Red),
+    }
+    if badges.commercial {
+        badge("commercial".to_string(), Color::Magenta);
+    }
+    if badges.downloaded {
+        badge("downloaded".to_string(), Color::Blue);
+    }
+    spans
+}
+
 /// UI rendering implementation for TuiApp
 impl TuiApp {
     /// Render the main UI layout
@@ -48,14 +80,7 @@ impl TuiApp {
         let titles = if self.is_online {
             vec!["Browse Games", "My Games"]
         } else {
-            vec!["My Games"]
-        };
-        
-        let current_tab = if self.is_online {
-            self.current_tab
-        } else {
-            // When offline, only one tab (My Games), always show it selected
-            0
+            vec!["Browse Games (cached)", "My Games"]
         };
         
         let tabs = Tabs::new(titles)
@@ -64,7 +89,7 @@ impl TuiApp {
                 .title(title))
             .style(Style::default().fg(Color::Gray))
             .highlight_style(Style::default().fg(Color::Yellow))
-            .select(current_tab);
+            .select(self.current_tab);
         f.render_widget(tabs, area);
     }
 
@@ -73,15 +98,12 @@ impl TuiApp {
         match self.state {
             AppState::GameDetails => self.render_game_details(f, area),
             AppState::SaveFilesDialog => self.render_saves_dialog(f, area),
+            AppState::TranscriptsDialog => self.render_transcripts_dialog(f, area),
+            AppState::TranscriptView => self.render_transcript_view(f, area),
+            AppState::FormatPicker => self.render_format_picker(f, area),
             _ => {
                 match self.current_tab {
-                    0 => {
-                        if self.is_online {
-                            self.render_browse_tab(f, area)
-                        } else {
-                            self.render_downloaded_tab(f, area)
-                        }
-                    },
+                    0 => self.render_browse_tab(f, area),
                     1 => self.render_downloaded_tab(f, area),
                     _ => {}
                 }
@@ -91,9 +113,9 @@ impl TuiApp {
 
     /// Render the browse/search tab
     pub(crate) fn render_browse_tab(&mut self, f: &mut Frame, area: Rect) {
-        if !self.is_online {
-            // Show offline message
-            let offline_msg = Paragraph::new("Network connection unavailable.\n\nBrowse and search features are disabled.\n\nPress Tab to view your downloaded games.")
+        if !self.is_online && self.search_results.is_empty() {
+            // Show offline message when there is nothing cached to browse
+            let offline_msg = Paragraph::new("Network connection unavailable.\n\nNo cached IFDB results to show.\n\nPress Tab to view your downloaded games.")
                 .block(create_block()
                     .borders(Borders::ALL)
                     .title("Browse Games - Offline"))
@@ -102,44 +124,75 @@ impl TuiApp {
             return;
         }
         
+        let input_height = match &self.search_filters_form {
+            Some(_) => SearchFiltersForm::FIELDS.len() as u16 + 2,
+            None => 3,
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Search input
-                Constraint::Min(0),    // Results
+                Constraint::Length(input_height), // Search input or filters
+                Constraint::Min(0),               // Results
             ])
             .split(area);
 
-        // Search input
-        let search_style = if self.input_mode == InputMode::Searching {
-            Style::default().fg(Color::Yellow)
+        if let Some(form) = &self.search_filters_form {
+            f.render_widget(Self::search_filters_form(form), chunks[0]);
         } else {
-            Style::default()
-        };
+            // Search input
+            let search_style = if self.input_mode == InputMode::Searching {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
 
-        let search_input = Paragraph::new(self.search_input.as_str())
-            .style(search_style)
-            .block(create_block()
-                .borders(Borders::ALL)
-                .title("Search (Press 's' to search, Enter to execute)"));
-        f.render_widget(search_input, chunks[0]);
+            let operators = self.search_filters.operators();
+            let search_title = if operators.is_empty() {
+                "Search (Press 's' to search, Enter to execute, 'f' for filters)".to_string()
+            } else {
+                format!("Search - Filters: {} ('f' to change)", operators.join(" "))
+            };
+            let search_input = Paragraph::new(self.search_input.as_str())
+                .style(search_style)
+                .block(create_block()
+                    .borders(Borders::ALL)
+                    .title(search_title));
+            f.render_widget(search_input, chunks[0]);
+
+            if self.input_mode == InputMode::Searching {
+                let column = self.search_input.cursor_column().min(chunks[0].width.saturating_sub(3));
+                f.set_cursor(chunks[0].x + 1 + column, chunks[0].y + 1);
+            }
+        }
 
         // Results list
         let items: Vec<ListItem> = self.search_results
             .iter()
-            .map(|game| {
+            .zip(&self.search_badges)
+            .map(|(game, badges)| {
                 let rating = game.star_rating
                     .map(|r| format!(" [{:.1}★]", r))
                     .unwrap_or_default();
                 
-                ListItem::new(format!("{} - {}{}", game.title, game.author, rating))
+                let mut spans = vec![Span::raw(format!("{} - {}{}", game.title, game.author, rating))];
+                spans.extend(badge_spans(badges));
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
+        let shown = if self.show_all_results { "All games" } else { "Games" };
+        let list_title = if !self.is_online {
+            format!("{} - Offline, cached results (Enter: Details, 'a': Show all)", shown)
+        } else if self.search_results_stale {
+            format!("{} - Cached, may be stale (Enter: Details, 'd': Download, 'a': Show all)", shown)
+        } else {
+            format!("{} (Enter: Details, 'd': Download, 'a': Show all)", shown)
+        };
+
         let list = List::new(items)
             .block(create_block()
                 .borders(Borders::ALL)
-                .title("Games (Enter: Details, 'd': Download)"))
+                .title(list_title))
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .highlight_symbol("> ");
 
@@ -157,14 +210,20 @@ impl TuiApp {
                     " (not played)".to_string()
                 };
                 
-                ListItem::new(format!("{} - {}{}", game.title, game.author, play_info))
+                let release = game.release
+                    .as_ref()
+                    .map(|r| format!(" [{}]", r))
+                    .unwrap_or_default();
+                let browser = if game.is_web() { " [browser]" } else { "" };
+
+                ListItem::new(format!("{} - {}{}{}{}", game.title, game.author, release, browser, play_info))
             })
             .collect();
 
         let list = List::new(items)
             .block(create_block()
                 .borders(Borders::ALL)
-                .title("Downloaded Games (Enter: Launch | v: View Saves | x: Delete)"))
+                .title("Downloaded Games (Enter: Launch | i: Details | v: View Saves | x: Delete)"))
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .highlight_symbol("> ");
 
@@ -223,16 +282,157 @@ impl TuiApp {
         f.render_stateful_widget(list, area, &mut self.save_selection);
     }
 
+    /// Render the list of recorded transcripts
+    pub(crate) fn render_transcripts_dialog(&mut self, f: &mut Frame, area: Rect) {
+        if self.transcripts.is_empty() {
+            let msg = "No transcripts recorded for this game.\n\nSet \"transcripts\": true in ~/.glkcli/settings.json to record play sessions.\n\nPress Esc to close.";
+
+            let paragraph = Paragraph::new(msg)
+                .block(create_block()
+                    .borders(Borders::ALL)
+                    .title("Transcripts"))
+                .wrap(Wrap { trim: true });
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self.transcripts
+            .iter()
+            .map(|t| {
+                let size = std::fs::metadata(&t.output).map(|m| m.len()).unwrap_or(0);
+                ListItem::new(format!("{} ({})", t.name(), format_size(size)))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(create_block()
+                .borders(Borders::ALL)
+                .title("Transcripts (Enter: Read | e: Export text | Esc: Close)"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+
+        f.render_stateful_widget(list, area, &mut self.transcript_selection);
+    }
+
+    /// Render the list of formats to pick from for an undetected game
+    pub(crate) fn render_format_picker(&mut self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.format_options
+            .iter()
+            .map(|(format, installed)| {
+                let interpreter = format.interpreter().unwrap_or("-");
+                if *installed {
+                    ListItem::new(format!("{} - {}", format.name(), interpreter))
+                } else {
+                    ListItem::new(format!("{} - {} (not installed)", format.name(), interpreter))
+                        .style(Style::default().fg(Color::DarkGray))
+                }
+            })
+            .collect();
+
+        let title = match &self.format_picker_game {
+            Some(game) => format!("Format of '{}' (Enter: Launch and remember | Esc: Cancel)", game.title),
+            None => "Choose a format (Enter: Launch and remember | Esc: Cancel)".to_string(),
+        };
+
+        let list = List::new(items)
+            .block(create_block()
+                .borders(Borders::ALL)
+                .title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+
+        f.render_stateful_widget(list, area, &mut self.format_selection);
+    }
+
+    /// Render a transcript as plain text
+    pub(crate) fn render_transcript_view(&mut self, f: &mut Frame, area: Rect) {
+        let name = self.transcript_selection
+            .selected()
+            .and_then(|i| self.transcripts.get(i))
+            .map(|t| t.name())
+            .unwrap_or_default();
+
+        let paragraph = Paragraph::new(self.transcript_text.as_str())
+            .block(create_block()
+                .borders(Borders::ALL)
+                .title(format!("{} (↑↓/PgUp/PgDn: Scroll | e: Export text | Esc: Back)", name)))
+            .wrap(Wrap { trim: false })
+            .scroll((self.transcript_scroll, 0));
+        f.render_widget(paragraph, area);
+    }
+
     /// Render the game details view
-    pub(crate) fn render_game_details(&self, f: &mut Frame, area: Rect) {
+    pub(crate) fn render_game_details(&mut self, f: &mut Frame, area: Rect) {
+        if self.current_cover.is_some() && self.graphics != GraphicsProtocol::None {
+            if self.cover_enlarged {
+                self.render_cover(f, area);
+                return;
+            }
+
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length((area.width / 3).min(36)), // Cover
+                    Constraint::Min(0),                           // Details
+                ])
+                .split(area);
+            self.render_cover(f, chunks[0]);
+            self.render_game_details_text(f, chunks[1]);
+        } else {
+            self.render_game_details_text(f, area);
+        }
+    }
+
+    /// Render the cover art panel of the details view
+    fn render_cover(&mut self, f: &mut Frame, area: Rect) {
+        let Some(cover) = &self.current_cover else {
+            return;
+        };
+
+        let title = if self.cover_enlarged { "Cover (c: Shrink)" } else { "Cover (c: Enlarge)" };
+        let block = create_block()
+            .borders(Borders::ALL)
+            .title(title);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let fitted = cover.fit(inner);
+        let size = (fitted.width, fitted.height);
+        let render: fn(&CoverImage, u16, u16) -> Vec<Line<'static>> = match self.graphics {
+            GraphicsProtocol::HalfBlocks => CoverImage::halfblock_lines,
+            GraphicsProtocol::Ascii => CoverImage::ascii_lines,
+            GraphicsProtocol::None => return,
+            // Real images are written to the terminal after the frame is drawn
+            _ => {
+                self.pending_cover_area = Some(fitted);
+                return;
+            }
+        };
+
+        // Resizing the image is too slow to repeat on every redraw
+        if self.cover_lines.as_ref().map(|(cached, _)| *cached) != Some(size) {
+            self.cover_lines = Some((size, render(cover, size.0, size.1)));
+        }
+        if let Some((_, lines)) = &self.cover_lines {
+            f.render_widget(Paragraph::new(lines.clone()), fitted);
+        }
+    }
+
+    /// Render the text part of the game details view
+    fn render_game_details_text(&self, f: &mut Frame, area: Rect) {
         if let Some(details) = &self.current_game_details {
-            // If in import mode, show import input at the top
-            let (details_area, import_input_area) = if self.input_mode == InputMode::ImportingFile {
+            // If in import mode or editing launch options, show the input at the top
+            let input_height = match self.input_mode {
+                InputMode::ImportingFile => 3,
+                InputMode::EditingLaunchOptions => LaunchOptionsForm::FIELDS.len() as u16 + 2,
+                _ => 0,
+            };
+            let (details_area, input_area) = if input_height > 0 {
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(3), // Import input
-                        Constraint::Min(0),    // Game details
+                        Constraint::Length(input_height), // Input
+                        Constraint::Min(0),               // Game details
                     ])
                     .split(area);
                 (chunks[1], Some(chunks[0]))
@@ -263,6 +463,19 @@ impl TuiApp {
                         Span::from(decode_html_entities(author)),
                     ]));
                 }
+
+                for (label, value) in [
+                    ("Genre: ", &biblio.genre),
+                    ("Language: ", &biblio.language),
+                    ("First published: ", &biblio.firstpublished),
+                ] {
+                    if let Some(value) = value {
+                        text.push(Line::from(vec![
+                            Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+                            Span::from(decode_html_entities(value)),
+                        ]));
+                    }
+                }
                 
                 // Show download status
                 if is_downloaded {
@@ -302,6 +515,17 @@ impl TuiApp {
                     }
                 }
                 
+                if let Some(game) = self.downloaded_games.iter().find(|g| g.tuid == tuid) {
+                    text.extend(Self::play_time_lines(game));
+                    text.extend(Self::launch_options_lines(&game.launch_options));
+                }
+
+                if let Some((_, info)) = self.current_story_info.as_ref().filter(|(t, _)| t == tuid) {
+                    text.extend(Self::story_info_lines(info));
+                }
+
+                text.extend(Self::ifdb_metadata_lines(details));
+
                 if let Some(desc) = &biblio.description {
                     text.push(Line::from(""));
                     text.push(Line::from(vec![
@@ -314,12 +538,17 @@ impl TuiApp {
             }
 
             let title = if is_downloaded {
-                "Game Details (Esc: Back) - Already Downloaded"
+                "Game Details (o: Launch options | Esc: Back) - Already Downloaded"
             } else if is_commercial {
                 "Game Details (i: Import | Esc: Back)"
             } else {
                 "Game Details (Esc: Back, 'd': Download)"
             };
+            let title = if self.current_details_stale {
+                format!("{} [cached - may be stale]", title)
+            } else {
+                title.to_string()
+            };
 
             let paragraph = Paragraph::new(text)
                 .block(create_block()
@@ -330,7 +559,7 @@ impl TuiApp {
             f.render_widget(paragraph, details_area);
 
             // Render import input if active
-            if let Some(input_area) = import_input_area {
+            if let (Some(input_area), InputMode::ImportingFile) = (input_area, self.input_mode) {
                 let import_style = Style::default().fg(Color::Green);
                 let import_input = Paragraph::new(self.import_file_path.as_str())
                     .style(import_style)
@@ -339,7 +568,218 @@ impl TuiApp {
                         .title("Enter file path (supports ~/ for home directory)"));
                 f.render_widget(import_input, input_area);
             }
+
+            if let (Some(input_area), Some(form)) = (input_area, &self.launch_options_form) {
+                f.render_widget(Self::launch_options_form(form), input_area);
+            }
+        }
+    }
+
+    /// Build the rating, play time, tag, IFID and download lines for a game
+    fn ifdb_metadata_lines(details: &GameDetails) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = Vec::new();
+
+        if let Some(format) = details.identification.as_ref().and_then(|i| i.format.as_ref()) {
+            lines.push(Line::from(vec![
+                Span::styled("Format: ", bold),
+                Span::from(format.clone()),
+            ]));
+        }
+
+        if let Some(ifdb) = &details.ifdb {
+            if let Some(rating) = ifdb.star_rating.or(ifdb.average_rating) {
+                let count = ifdb.rating_count_tot
+                    .map(|n| format!(" ({} ratings)", n))
+                    .unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled("Rating: ", bold),
+                    Span::from(format!("{:.1}★{}", rating, count)),
+                ]));
+            }
+
+            if let Some(minutes) = ifdb.play_time_in_minutes {
+                let play_time = std::time::Duration::from_secs(u64::from(minutes) * 60);
+                lines.push(Line::from(vec![
+                    Span::styled("Play time: ", bold),
+                    Span::from(format_duration(play_time)),
+                ]));
+            }
+
+            if let Some(tags) = ifdb.tags.as_ref().filter(|t| !t.is_empty()) {
+                let names: Vec<String> = tags.iter()
+                    .map(|t| decode_html_entities(&t.name))
+                    .collect();
+                lines.push(Line::from(vec![
+                    Span::styled("Tags: ", bold),
+                    Span::from(names.join(", ")),
+                ]));
+            }
+        }
+
+        if let Some(ifids) = details.identification.as_ref().and_then(|i| i.ifids.as_ref()) {
+            if !ifids.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("IFIDs: ", bold),
+                    Span::from(ifids.join(", ")),
+                ]));
+            }
+        }
+
+        if let Some(downloads) = details.ifdb.as_ref().and_then(|i| i.downloads.as_ref()) {
+            if !downloads.links.is_empty() {
+                lines.push(Line::from(Span::styled("Downloads:", bold)));
+                for link in &downloads.links {
+                    let format = link.format.as_deref().unwrap_or("unknown format");
+                    lines.push(Line::from(format!("  • {} ({})", decode_html_entities(&link.title), format)));
+                }
+            }
         }
+
+        lines
+    }
+
+    /// Lines summarising the recorded play sessions of a downloaded game
+    fn play_time_lines(game: &LocalGame) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        let Some(last) = game.last_session() else {
+            return Vec::new();
+        };
+
+        vec![
+            Line::from(vec![
+                Span::styled("Time played: ", bold),
+                Span::from(format!(
+                    "{} over {} sessions",
+                    format_duration(game.total_play_time()),
+                    game.sessions.len()
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled("Last session: ", bold),
+                Span::from(format_duration(last.duration())),
+            ]),
+        ]
+    }
+
+    /// Lines describing the launch options pinned for a downloaded game
+    fn launch_options_lines(options: &LaunchOptions) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        if options.is_empty() {
+            return Vec::new();
+        }
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![Span::styled("Launch options", bold)]),
+        ];
+        let fields = [
+            ("Format", options.format.map(|f| f.name().to_string())),
+            ("Interpreter", options.interpreter.clone()),
+            ("Arguments", Some(options.args_string()).filter(|a| !a.is_empty())),
+            ("Environment", Some(options.env_string()).filter(|e| !e.is_empty())),
+            ("Hooks", (!options.hooks.is_empty()).then(|| format!(
+                "{} before, {} after launch",
+                options.hooks.pre_launch.len(),
+                options.hooks.post_launch.len()
+            ))),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {}: ", name), bold),
+                    Span::from(value),
+                ]));
+            }
+        }
+        lines
+    }
+
+    /// The launch options form, with the field being edited highlighted
+    fn launch_options_form(form: &LaunchOptionsForm) -> Paragraph<'static> {
+        let values = [
+            form.format.map(|f| format!("< {} >", f.name())).unwrap_or_else(|| "< detect >".to_string()),
+            form.interpreter.clone(),
+            form.args.clone(),
+            form.env.clone(),
+        ];
+
+        let lines: Vec<Line> = LaunchOptionsForm::FIELDS
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let style = if i == form.field {
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<13}", format!("{}:", name)), style),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(create_block()
+                .borders(Borders::ALL)
+                .title("Launch options (NAME=value for environment, empty for defaults)"))
+    }
+
+    /// The search filters form, with the field being edited highlighted
+    fn search_filters_form(form: &SearchFiltersForm) -> Paragraph<'static> {
+        let choice = |value: Option<String>| format!("< {} >", value.unwrap_or_else(|| "any".to_string()));
+        let values = [
+            form.genre.clone(),
+            form.system.clone(),
+            choice(form.min_rating.map(|r| format!("{}★ or more", r))),
+            form.year_from.clone(),
+            form.year_to.clone(),
+            form.language.clone(),
+            choice(form.play_time.map(|p| p.name().to_string())),
+        ];
+
+        let lines: Vec<Line> = SearchFiltersForm::FIELDS
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let style = if i == form.field {
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<12}", format!("{}:", name)), style),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(create_block()
+                .borders(Borders::ALL)
+                .title("Search filters (empty for any)"))
+    }
+
+    /// Lines listing the header fields of a downloaded game's story file
+    fn story_info_lines(info: &StoryInfo) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![Span::styled(format!("Story file ({})", info.format), bold)]),
+        ];
+        for field in &info.fields {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", field.name), bold),
+                Span::from(field.value.clone()),
+            ]));
+        }
+
+        lines
     }
 
     /// Render the status bar at the bottom
@@ -350,9 +790,14 @@ impl TuiApp {
             msg.clone()
         } else {
             match self.input_mode {
-                InputMode::Searching => "Search mode - Type to search, Enter to execute, Esc to cancel".to_string(),
-                InputMode::Confirmation => "Confirm action? (y/n)".to_string(),
+                InputMode::Searching => "Search mode - Type to search, ←→/Home/End to move, Enter to execute, Esc to cancel".to_string(),
+                InputMode::Confirmation => self.pending_action
+                    .as_ref()
+                    .map(|a| a.prompt())
+                    .unwrap_or_else(|| "Confirm action? (y/n)".to_string()),
                 InputMode::ImportingFile => "Import mode - Enter file path, Enter to confirm, Esc to cancel".to_string(),
+                InputMode::EditingLaunchOptions => "↑↓: Field | ←→: Format | Enter: Save | Esc: Cancel".to_string(),
+                InputMode::EditingSearchFilters => "↑↓: Field | ←→: Rating/Play time | Enter: Search | Esc: Cancel".to_string(),
                 InputMode::Normal => {
                     // Context-aware status based on current tab
                     let base = "q: Quit | Tab: Switch";
@@ -366,8 +811,14 @@ impl TuiApp {
                                 .unwrap_or("");
                             let is_downloaded = self.storage.is_game_downloaded(tuid).unwrap_or(false);
                             
+                            let base = if self.current_cover.is_some() {
+                                format!("{} | c: Cover", base)
+                            } else {
+                                base.to_string()
+                            };
+                            
                             if is_downloaded {
-                                format!("{} | ↑↓: Navigate | Esc: Back", base)
+                                format!("{} | ↑↓: Navigate | o: Launch options | Esc: Back", base)
                             } else {
                                 // Check if current game is commercial
                                 let is_commercial = self.current_game_details
@@ -384,8 +835,9 @@ impl TuiApp {
                         }
                         _ => {
                             match self.current_tab {
-                                0 => format!("{} | s: Search | d: Download | r: Refresh", base),
-                                1 => format!("{} | x: Delete | r: Refresh", base),
+                                0 if !self.is_online => format!("{} | s: Search cache | r: Recheck network", base),
+                                0 => format!("{} | s: Search | d: Download | f: Filters | a: Show all | r: Refresh", base),
+                                1 => format!("{} | i: Details | t: Transcripts | x: Delete | u: Undo delete | r: Refresh", base),
                                 2 => format!("{} | r: Refresh", base),
                                 _ => base.to_string(),
                             }
@@ -21,6 +21,34 @@ pub fn decode_html_entities(text: &str) -> String {
     html_escape::decode_html_entities(text).to_string()
 }
 
+/// Format a byte count for display, e.g. "1.5 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
+/// Format a duration for display, e.g. "2h 05m", "12m" or "40s"
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes) = (secs / 3600, (secs % 3600) / 60);
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +119,19 @@ mod tests {
         let expected = "<tag> & more";
         assert_eq!(decode_html_entities(input), expected);
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512.0 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        use std::time::Duration;
+        assert_eq!(format_duration(Duration::from_secs(40)), "40s");
+        assert_eq!(format_duration(Duration::from_secs(12 * 60 + 5)), "12m");
+        assert_eq!(format_duration(Duration::from_secs(2 * 3600 + 5 * 60)), "2h 05m");
+    }
 }
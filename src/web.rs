@@ -0,0 +1,125 @@
+//! Games played in a web browser
+//!
+//! Twine, ink, ChoiceScript, Dendry and similar systems publish games as web
+//! pages, which no GLK interpreter can run. glkcli opens them in a browser
+//! instead. Games that can only be played online are kept in the library as
+//! an Internet Shortcut (`.url`) file holding their address, so they are
+//! listed and launched like any other game file.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// First line of an Internet Shortcut file
+pub const SHORTCUT_HEADER: &str = "[InternetShortcut]";
+
+/// Placeholder for the page in a browser command, as in `$BROWSER`
+const TARGET_PLACEHOLDER: &str = "%s";
+
+/// Contents of an Internet Shortcut file pointing at `url`
+pub fn shortcut_contents(url: &str) -> String {
+    format!("{}\r\nURL={}\r\n", SHORTCUT_HEADER, url)
+}
+
+/// What to hand the browser for a web game: the address in a shortcut
+/// file, or the path of a local page
+pub fn browser_target(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read web game: {}", path.display()))?;
+    if !bytes.starts_with(SHORTCUT_HEADER.as_bytes()) {
+        return Ok(path.display().to_string());
+    }
+
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("URL="))
+        .map(|url| url.to_string())
+        .with_context(|| format!("Shortcut has no URL: {}", path.display()))
+}
+
+/// The command used to open web games
+///
+/// `GLKCLI_BROWSER` wins over the configured command, then `$BROWSER`, and
+/// otherwise the desktop's own opener is used.
+pub fn browser_command_line(configured: Option<&str>) -> String {
+    env::var("GLKCLI_BROWSER")
+        .ok()
+        .filter(|command| !command.trim().is_empty())
+        .or_else(|| configured.map(str::to_string))
+        .or_else(|| env::var("BROWSER").ok().filter(|command| !command.trim().is_empty()))
+        .unwrap_or_else(|| default_opener().to_string())
+}
+
+fn default_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Split a browser command into its program and arguments, with `target`
+/// in place of `%s`, or added at the end if there is no `%s`
+///
+/// Words are quoted as in a shell, so paths with spaces can be used. Returns
+/// `None` for an empty command and an error for unbalanced quotes.
+pub fn split_command(command_line: &str, target: &str) -> Result<Option<(String, Vec<String>)>> {
+    let mut words = shell_words::split(command_line)
+        .with_context(|| format!("Invalid browser command: {}", command_line))?
+        .into_iter();
+    let Some(program) = words.next() else {
+        return Ok(None);
+    };
+
+    let mut args: Vec<String> = words.collect();
+    if args.iter().any(|arg| arg.contains(TARGET_PLACEHOLDER)) {
+        for arg in &mut args {
+            *arg = arg.replace(TARGET_PLACEHOLDER, target);
+        }
+    } else {
+        args.push(target.to_string());
+    }
+
+    Ok(Some((program, args)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_browser_target() {
+        let temp = TempDir::new().unwrap();
+        let shortcut = temp.path().join("game.url");
+        fs::write(&shortcut, shortcut_contents("https://example.com/play")).unwrap();
+        assert_eq!(browser_target(&shortcut).unwrap(), "https://example.com/play");
+
+        let page = temp.path().join("index.html");
+        fs::write(&page, "<!DOCTYPE html><html></html>").unwrap();
+        assert_eq!(browser_target(&page).unwrap(), page.display().to_string());
+
+        fs::write(&shortcut, SHORTCUT_HEADER).unwrap();
+        assert!(browser_target(&shortcut).is_err());
+    }
+
+    #[test]
+    fn test_split_command() {
+        let (program, args) = split_command("firefox --new-window", "game.html").unwrap().unwrap();
+        assert_eq!(program, "firefox");
+        assert_eq!(args, vec!["--new-window", "game.html"]);
+
+        let (_, args) = split_command("w3m -o confirm_qq=0 %s", "game.html").unwrap().unwrap();
+        assert_eq!(args, vec!["-o", "confirm_qq=0", "game.html"]);
+
+        let (program, args) = split_command(r#""/opt/My Browser/browser" --new-window %s"#, "game.html")
+            .unwrap()
+            .unwrap();
+        assert_eq!(program, "/opt/My Browser/browser");
+        assert_eq!(args, vec!["--new-window", "game.html"]);
+
+        assert!(split_command("  ", "game.html").unwrap().is_none());
+        assert!(split_command("'/opt/My Browser/browser %s", "game.html").is_err());
+    }
+}
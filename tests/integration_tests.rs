@@ -2,6 +2,7 @@
 // These tests verify end-to-end functionality
 
 use assert_fs::prelude::*;
+use assert_fs::fixture::ChildPath;
 use assert_fs::TempDir;
 use std::process::Command;
 
@@ -167,3 +168,573 @@ fn test_verbose_flag() {
         stdout
     );
 }
+
+#[test]
+fn test_gc_removes_orphaned_game_directory() {
+    let home = TempDir::new().unwrap();
+    let orphan = home.child(".glkcli/games/Orphan_abcdefgh/orphan.z5");
+    orphan.write_binary(&[5u8; 64]).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("HOME", home.path())
+        .args(["gc", "--dry-run"])
+        .output()
+        .expect("Failed to execute glkcli");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Orphan_abcdefgh"));
+    assert!(stdout.contains("Would free"));
+    assert!(orphan.path().exists());
+
+    let output = Command::new(get_binary_path())
+        .env("HOME", home.path())
+        .arg("gc")
+        .output()
+        .expect("Failed to execute glkcli");
+
+    assert!(output.status.success());
+    assert!(!home.child(".glkcli/games/Orphan_abcdefgh").path().exists());
+}
+
+#[test]
+fn test_stats_json_export() {
+    let home = TempDir::new().unwrap();
+    let time = |secs: u64| format!(r#"{{ "secs_since_epoch": {}, "nanos_since_epoch": 0 }}"#, secs);
+    let metadata = format!(
+        r#"{{
+            "version": 1,
+            "saves": {{}},
+            "games": {{
+                "abcdefgh12345678": {{
+                    "tuid": "abcdefgh12345678",
+                    "title": "Test Game",
+                    "author": "Tester",
+                    "file_path": "/nonexistent/test.z5",
+                    "download_date": {start},
+                    "file_size": 10,
+                    "format": "zcode",
+                    "play_count": 1,
+                    "last_played": {end},
+                    "description": null,
+                    "cover_art_path": null,
+                    "sessions": [{{
+                        "started_at": {start},
+                        "ended_at": {end},
+                        "duration_secs": 1500,
+                        "interpreter": "bocfel",
+                        "exit_code": 0
+                    }}]
+                }}
+            }}
+        }}"#,
+        start = time(1_700_000_000),
+        end = time(1_700_001_500),
+    );
+    home.child(".glkcli/metadata.json").write_str(&metadata).unwrap();
+
+    let output = Command::new(get_binary_path())
+        .env("HOME", home.path())
+        .args(["stats", "--json"])
+        .output()
+        .expect("Failed to execute glkcli");
+
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats[0]["title"], "Test Game");
+    assert_eq!(stats[0]["total_seconds"], 1500);
+    assert_eq!(stats[0]["sessions"][0]["interpreter"], "bocfel");
+    assert_eq!(stats[0]["sessions"][0]["started_at"], "2023-11-14T22:13:20+00:00");
+}
+
+#[test]
+fn test_verify_library() {
+    let home = TempDir::new().unwrap();
+
+    // A Glulx story whose checksum matches
+    let mut story = vec![0u8; 256];
+    story[0..4].copy_from_slice(b"Glul");
+    story[12..16].copy_from_slice(&256u32.to_be_bytes());
+    story[100] = 7;
+    let checksum = story
+        .chunks(4)
+        .fold(0u32, |sum, w| sum.wrapping_add(u32::from_be_bytes(w.try_into().unwrap())));
+    story[32..36].copy_from_slice(&checksum.to_be_bytes());
+    home.child("games/good.ulx").write_binary(&story).unwrap();
+
+    let game = |tuid: &str, title: &str, path: &std::path::Path| {
+        format!(
+            r#""{tuid}": {{
+                "tuid": "{tuid}", "title": "{title}", "author": "Tester",
+                "file_path": "{path}",
+                "download_date": {{ "secs_since_epoch": 1700000000, "nanos_since_epoch": 0 }},
+                "file_size": 256, "format": null, "play_count": 0, "last_played": null,
+                "description": null, "cover_art_path": null
+            }}"#,
+            path = path.display()
+        )
+    };
+    let metadata = format!(
+        r#"{{ "version": 1, "saves": {{}}, "games": {{ {}, {} }} }}"#,
+        game("aaaaaaaaaaaaaaaa", "Good Game", home.child("games/good.ulx").path()),
+        game("bbbbbbbbbbbbbbbb", "Lost Game", home.child("games/lost.z5").path()),
+    );
+    home.child(".glkcli/metadata.json").write_str(&metadata).unwrap();
+
+    let output = run_isolated(&home, &["verify"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("Good Game") && stdout.contains("hash recorded"), "{}", stdout);
+    assert!(stdout.contains("MISSING"), "{}", stdout);
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(home.child(".glkcli/metadata.json").path()).unwrap()).unwrap();
+    assert_eq!(metadata["games"]["aaaaaaaaaaaaaaaa"]["sha256"].as_str().map(str::len), Some(64));
+
+    // Once the hash is recorded, a change to the file is noticed
+    story[200] = 1;
+    home.child("games/good.ulx").write_binary(&story).unwrap();
+    let output = run_isolated(&home, &["verify"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("DAMAGED"));
+}
+
+#[test]
+fn test_transcript_to_plain_text() {
+    let temp = TempDir::new().unwrap();
+    let transcript = temp.child("2025-01-01_12-00-00.out");
+    transcript
+        .write_binary(b"\x1b[2J\x1b[1;1H\x1b[1mWest of House\x1b[0m\r\n>open mailbox\r\n")
+        .unwrap();
+
+    let output = Command::new(get_binary_path())
+        .arg("transcript")
+        .arg(transcript.path())
+        .output()
+        .expect("Failed to execute glkcli");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "West of House\n>open mailbox\n");
+}
+
+/// Write an executable shell script at `name` under `dir` running `body`
+fn fake_interpreter(dir: &TempDir, name: &str, body: &str) -> ChildPath {
+    let script = dir.child(name);
+    script.write_str(&format!("#!/bin/sh\n{}", body)).unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    script
+}
+
+#[test]
+fn test_run_script_matches_expected_transcript() {
+    let temp = TempDir::new().unwrap();
+    // A fake bocfel that prompts with '>' and answers each command
+    fake_interpreter(
+        &temp,
+        "bin/bocfel",
+        "echo 'West of House'\nprintf '>'\nwhile read line; do echo \"You said: $line\"; printf '>'; done\n",
+    );
+
+    write_fake_zcode(&temp);
+    temp.child("commands.txt").write_str("# walkthrough\nopen mailbox\n").unwrap();
+    temp.child("good.txt")
+        .write_str("West of House\n>open mailbox\nYou said: open mailbox\n>\n")
+        .unwrap();
+    temp.child("bad.txt")
+        .write_str("West of House\n>open mailbox\nIt is locked.\n>\n")
+        .unwrap();
+
+    let run = |expected: &str| {
+        run_isolated(
+            &temp,
+            &["run", "game.z5", "--script", "commands.txt", "--idle-ms", "300", "--expect", expected],
+        )
+    };
+
+    let output = run("good.txt");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("You said: open mailbox"));
+
+    let output = run("bad.txt");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not match"));
+    assert!(stderr.contains("line 3"));
+}
+
+#[test]
+fn test_interpreter_exit_code_rules() {
+    let temp = TempDir::new().unwrap();
+    fake_interpreter(&temp, "bin/bocfel", "exit 5\n");
+
+    write_fake_zcode(&temp);
+
+    let run = || run_isolated(&temp, &["game.z5"]);
+
+    let output = run();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("error code: 5"));
+
+    // Declaring 5 a normal exit for bocfel in the settings makes it succeed
+    temp.child(".glkcli/settings.json")
+        .write_str(r#"{ "exit_codes": { "bocfel": [5] } }"#)
+        .unwrap();
+    assert!(run().status.success());
+}
+
+/// Run glkcli in `dir` with a PATH holding only `bin`, so no real interpreters are found
+fn run_isolated(dir: &TempDir, args: &[&str]) -> std::process::Output {
+    let bin = dir.child("bin");
+    bin.create_dir_all().unwrap();
+    Command::new(get_binary_path())
+        .env("HOME", dir.path())
+        .env("PATH", bin.path())
+        .current_dir(dir.path())
+        .args(args)
+        .output()
+        .expect("Failed to execute glkcli")
+}
+
+/// Write a minimal version 5 Z-code story as `game.z5` in `dir`
+fn write_fake_zcode(dir: &TempDir) {
+    let mut data = vec![0u8; 64];
+    data[0] = 5;
+    dir.child("game.z5").write_binary(&data).unwrap();
+}
+
+#[test]
+fn test_exit_code_usage() {
+    let temp = TempDir::new().unwrap();
+    assert_eq!(run_isolated(&temp, &["--invalid-flag"]).status.code(), Some(2));
+    assert_eq!(run_isolated(&temp, &["run", "game.z5", "--expect", "out.txt"]).status.code(), Some(2));
+    assert_eq!(run_isolated(&temp, &["search", "--min-rating", "6"]).status.code(), Some(2));
+    assert_eq!(run_isolated(&temp, &["search", "--play-time", "forever"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_exit_code_file_not_found() {
+    let temp = TempDir::new().unwrap();
+    assert_eq!(run_isolated(&temp, &["missing.z5"]).status.code(), Some(3));
+    assert_eq!(run_isolated(&temp, &["--format", "missing.z5"]).status.code(), Some(3));
+}
+
+#[test]
+fn test_exit_code_unknown_format() {
+    let temp = TempDir::new().unwrap();
+    temp.child("notes.txt").write_binary(&[0xAA; 64]).unwrap();
+
+    assert_eq!(run_isolated(&temp, &["notes.txt"]).status.code(), Some(4));
+
+    let output = run_isolated(&temp, &["--format", "notes.txt"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Unknown"));
+}
+
+#[test]
+fn test_exit_code_interpreter_missing() {
+    let temp = TempDir::new().unwrap();
+    write_fake_zcode(&temp);
+
+    let output = run_isolated(&temp, &["game.z5"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'bocfel' not found"));
+}
+
+#[test]
+fn test_exit_code_interpreter_failed() {
+    let temp = TempDir::new().unwrap();
+    fake_interpreter(&temp, "bin/bocfel", "exit 9\n");
+    write_fake_zcode(&temp);
+
+    assert_eq!(run_isolated(&temp, &["game.z5"]).status.code(), Some(6));
+    assert_eq!(run_isolated(&temp, &["run", "game.z5"]).status.code(), Some(6));
+}
+
+#[test]
+fn test_format_and_interpreter_overrides() {
+    let temp = TempDir::new().unwrap();
+    for name in ["bin/bocfel", "tools/myterp"] {
+        fake_interpreter(&temp, name, &format!("echo \"{} $@\"\n", name));
+    }
+    temp.child("notes.txt").write_binary(&[0xAA; 64]).unwrap();
+
+    assert_eq!(run_isolated(&temp, &["notes.txt"]).status.code(), Some(4));
+
+    let output = run_isolated(&temp, &["--format-override", "zcode", "notes.txt"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("bin/bocfel"));
+
+    let output = run_isolated(
+        &temp,
+        &["run", "notes.txt", "--format-override", "Z-code", "--interpreter", "tools/myterp"],
+    );
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("tools/myterp"));
+    assert!(stdout.contains("notes.txt"));
+
+    assert_eq!(run_isolated(&temp, &["--format-override", "pdf", "notes.txt"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_launch_hooks() {
+    let temp = TempDir::new().unwrap();
+    let script = fake_interpreter(&temp, "bin/bocfel", "exit 0\n");
+    write_fake_zcode(&temp);
+
+    let settings = temp.child(".glkcli/settings.json");
+    settings
+        .write_str(r#"{ "hooks": {
+            "pre_launch": ["echo \"$GLKCLI_HOOK $GLKCLI_GAME_FORMAT\" >> hooks.log"],
+            "post_launch": ["echo \"$GLKCLI_HOOK $GLKCLI_EXIT_CODE\" >> hooks.log"]
+        } }"#)
+        .unwrap();
+    let output = run_isolated(&temp, &["game.z5"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        std::fs::read_to_string(temp.child("hooks.log").path()).unwrap(),
+        "pre_launch zcode\npost_launch 0\n"
+    );
+
+    // A failing pre-launch hook stops the game from starting
+    settings
+        .write_str(r#"{ "hooks": { "pre_launch": ["exit 1"], "post_launch": ["touch after"] } }"#)
+        .unwrap();
+    let output = run_isolated(&temp, &["game.z5"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Pre-launch hook failed"));
+    assert!(!temp.child("after").path().exists());
+
+    // Post-launch hooks still run when the interpreter cannot be started
+    script.write_str("#!/nonexistent/sh\nexit 0\n").unwrap();
+    std::fs::remove_file(temp.child("hooks.log").path()).unwrap();
+    settings
+        .write_str(r#"{ "hooks": {
+            "pre_launch": ["echo \"$GLKCLI_HOOK\" >> hooks.log"],
+            "post_launch": ["echo \"$GLKCLI_HOOK [$GLKCLI_EXIT_CODE]\" >> hooks.log"]
+        } }"#)
+        .unwrap();
+    let output = run_isolated(&temp, &["game.z5"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        std::fs::read_to_string(temp.child("hooks.log").path()).unwrap(),
+        "pre_launch\npost_launch []\n"
+    );
+}
+
+#[test]
+fn test_web_game_uses_browser_command() {
+    let temp = TempDir::new().unwrap();
+    fake_interpreter(&temp, "bin/fakebrowser", "echo \"opening $@\"\n");
+    temp.child("story.html")
+        .write_str("<!DOCTYPE html>\n<html><body>You are in a maze.</body></html>\n")
+        .unwrap();
+    temp.child(".glkcli/settings.json")
+        .write_str(r#"{ "browser": "fakebrowser --private %s" }"#)
+        .unwrap();
+
+    let output = run_isolated(&temp, &["--format", "story.html"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Web"));
+
+    let output = run_isolated(&temp, &["story.html"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("opening --private"));
+    assert!(stdout.contains("story.html"));
+}
+
+#[test]
+fn test_format_json() {
+    let temp = TempDir::new().unwrap();
+    let mut data = vec![0u8; 64];
+    data[0..4].copy_from_slice(b"Glul");
+    temp.child("game.ulx").write_binary(&data).unwrap();
+
+    let output = run_isolated(&temp, &["--format", "--json", "game.ulx"]);
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["format"], "Glulx");
+    assert_eq!(report["method"], "header");
+    assert_eq!(report["confidence"], "high");
+    assert_eq!(report["interpreter"], "git");
+    assert_eq!(report["interpreter_found"], false);
+}
+
+#[test]
+fn test_inspect_json() {
+    let temp = TempDir::new().unwrap();
+    let mut data = vec![0u8; 64];
+    data[0..4].copy_from_slice(b"Glul");
+    data[4..8].copy_from_slice(&0x0003_0102u32.to_be_bytes());
+    data[36..40].copy_from_slice(b"Info");
+    data[52..54].copy_from_slice(&3u16.to_be_bytes());
+    data[54..60].copy_from_slice(b"160512");
+    temp.child("game.ulx").write_binary(&data).unwrap();
+
+    let output = run_isolated(&temp, &["inspect", "--json", "game.ulx"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["format"], "Glulx");
+    assert_eq!(info["release"], "Release 3 / 160512");
+    assert_eq!(info["fields"][0]["name"], "Glulx version");
+    assert_eq!(info["fields"][0]["value"], "3.1.2");
+
+    let output = run_isolated(&temp, &["inspect", "missing.z5"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_detect_directory_json() {
+    let temp = TempDir::new().unwrap();
+    let mut data = vec![0u8; 64];
+    data[0] = 5;
+    temp.child("games/zork.z5").write_binary(&data).unwrap();
+    temp.child("games/nested/story.ulx").write_binary(&[0xFF; 64]).unwrap();
+    temp.child("games/readme.txt").write_str("Not a game").unwrap();
+
+    let output = run_isolated(&temp, &["detect", "--json", "games"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let reports: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(reports.len(), 3);
+
+    let find = |name: &str| {
+        reports
+            .iter()
+            .find(|r| r["file"].as_str().unwrap().ends_with(name))
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(find("story.ulx")["method"], "extension");
+    assert_eq!(find("story.ulx")["confidence"], "low");
+    assert_eq!(find("zork.z5")["format"], "Z-code");
+    assert_eq!(find("readme.txt")["format"], "Unknown");
+
+    assert_eq!(run_isolated(&temp, &["detect", "missing.z5"]).status.code(), Some(3));
+}
+
+#[test]
+fn test_search_uses_configured_ifdb() {
+    let temp = TempDir::new().unwrap();
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/search")
+        .match_query(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::UrlEncoded("searchfor".into(), "zork genre:fantasy rating:4-".into()),
+            mockito::Matcher::UrlEncoded("count".into(), "20".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(std::fs::read_to_string("tests/fixtures/ifdb/search.json").unwrap())
+        .expect(1)
+        .create();
+
+    temp.child(".glkcli/settings.json")
+        .write_str(&format!(r#"{{ "ifdb_url": "{}" }}"#, server.url()))
+        .unwrap();
+
+    let args = ["search", "zork", "--genre", "fantasy", "--min-rating", "4", "--json"];
+    let output = run_isolated(&temp, &args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let hits: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(hits[0]["tuid"], "0dbnusxunq7fw5ro");
+    assert_eq!(hits[1]["title"], "Café Zork");
+    assert_eq!(hits[1]["system"], "Twine");
+
+    // The repeated search is answered from the response cache
+    let output = run_isolated(&temp, &args);
+    assert!(output.status.success());
+    mock.assert();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sigterm_restores_terminal_from_tui() {
+    use nix::pty::{openpty, Winsize};
+    use nix::sys::termios::{tcgetattr, LocalFlags};
+    use std::io::Read;
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    let temp = TempDir::new().unwrap();
+    temp.child("bin").create_dir_all().unwrap();
+    let winsize = Winsize { ws_row: 24, ws_col: 80, ws_xpixel: 0, ws_ypixel: 0 };
+    let pty = openpty(&winsize, None).unwrap();
+    let slave = std::fs::File::from(pty.slave);
+
+    let mut cmd = Command::new(get_binary_path());
+    cmd.env("HOME", temp.path())
+        .env("PATH", temp.child("bin").path())
+        .env("TERM", "xterm")
+        .env("GLKCLI_COVER_ART", "none")
+        // Nothing listens here, so the startup browse fails at once
+        .env("GLKCLI_IFDB_URL", "http://127.0.0.1:9")
+        .arg("--assume-online")
+        .current_dir(temp.path())
+        .stdin(Stdio::from(slave.try_clone().unwrap()))
+        .stdout(Stdio::from(slave.try_clone().unwrap()))
+        .stderr(Stdio::from(slave.try_clone().unwrap()));
+    // SAFETY: only async-signal-safe libc calls are made between fork and exec
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = cmd.spawn().unwrap();
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let reader_output = Arc::clone(&output);
+    let mut master = std::fs::File::from(pty.master);
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        while let Ok(n) = master.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            reader_output.lock().unwrap().extend_from_slice(&buf[..n]);
+        }
+    });
+    let seen = |sequence: &[u8]| output.lock().unwrap().windows(sequence.len()).any(|w| w == sequence);
+
+    // Wait for the TUI to take over the screen
+    let started = Instant::now();
+    while !seen(b"\x1b[?1049h") && started.elapsed() < Duration::from_secs(20) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(seen(b"\x1b[?1049h"), "TUI never entered the alternate screen");
+    assert!(!tcgetattr(&slave).unwrap().local_flags.contains(LocalFlags::ICANON));
+
+    // SAFETY: kill has no memory safety requirements
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        assert!(started.elapsed() < Duration::from_secs(10), "glkcli ignored SIGTERM");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    assert!(status.success(), "glkcli was killed: {:?}", status);
+    let flags = tcgetattr(&slave).unwrap().local_flags;
+    assert!(flags.contains(LocalFlags::ICANON | LocalFlags::ECHO));
+    let started = Instant::now();
+    while !seen(b"\x1b[?1049l") && started.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(seen(b"\x1b[?1049l"), "alternate screen was not left");
+}